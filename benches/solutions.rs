@@ -0,0 +1,61 @@
+//! Benchmarks both parts of every day on its `input` file with std only, so that `cargo bench` works on stable.
+//!
+//! `cargo bench [filter]` runs the benchmarks whose name, e.g. `day05::part2`, contains the filter. Each one is
+//! timed over batches of runs, long enough for the clock to be precise, and the median time per run is reported
+//! along with the spread between the fastest and the slowest batch, like `#[bench]` does.
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// Shortest duration of a batch of runs.
+const BATCH: Duration = Duration::from_millis(1);
+/// Batches timed per benchmark, unless they take longer than `BUDGET` in total.
+const BATCHES: usize = 50;
+const BUDGET: Duration = Duration::from_secs(3);
+
+/// Median and spread of the time per run of `f`.
+fn bench(mut f: impl FnMut()) -> (Duration, Duration) {
+    let now = Instant::now();
+    f();
+    let once = now.elapsed().max(Duration::from_nanos(1));
+    let runs = (BATCH.as_nanos() / once.as_nanos()).clamp(1, u32::MAX as u128) as u32;
+
+    let start = Instant::now();
+    let mut samples = Vec::with_capacity(BATCHES);
+    while samples.len() < BATCHES && (samples.len() < 5 || start.elapsed() < BUDGET) {
+        let now = Instant::now();
+        for _ in 0..runs {
+            f();
+        }
+        samples.push(now.elapsed() / runs);
+    }
+    samples.sort_unstable();
+    (samples[samples.len() / 2], samples[samples.len() - 1] - samples[0])
+}
+
+fn main() {
+    // Cargo passes `--bench`, and possibly other flags meant for libtest.
+    let filters: Vec<String> = std::env::args().skip(1).filter(|arg| !arg.starts_with('-')).collect();
+    for (day, solution) in aoc2023::build_solutions() {
+        for part in [1, 2] {
+            let name = format!("{day}::part{part}");
+            if !filters.is_empty() && !filters.iter().any(|filter| name.contains(filter.as_str())) {
+                continue;
+            }
+            let input = solution.input();
+            if let Err(e) = solution.solve(part, input) {
+                println!("bench {name:<16} ... error: {e:#}");
+                continue;
+            }
+            let (median, spread) = bench(|| {
+                let _ = black_box(solution.solve(part, black_box(input)));
+            });
+            println!(
+                "bench {name:<16} ... {:>12} ns/iter (+/- {})",
+                median.as_nanos(),
+                spread.as_nanos()
+            );
+        }
+    }
+}
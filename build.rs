@@ -0,0 +1,112 @@
+//! Registers the days: each `src/dayNN/mod.rs` becomes the module `dayNN` of the crate, and is added to
+//! `build_solutions`, by the `aoc!` call generated in `$OUT_DIR/days.rs`. So does each named input of a day,
+//! `src/dayNN/inputs/<name>.txt`, with its expected answers in the optional `<name>.part1` and `<name>.part2`.
+//!
+//! Every day is compiled with the `all` feature, and otherwise only with its feature of the same name, for building a
+//! subset of the days. Either way, an enabled day gets a `dayNN` cfg here for `aoc!` to test: `cfg(feature = ...)`
+//! only takes a string literal.
+use std::{
+    env,
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+/// Whether `name` is that of a day's directory, `day01` to `day25`.
+fn is_day(name: &str) -> bool {
+    name.strip_prefix("day")
+        .filter(|n| n.len() == 2)
+        .and_then(|n| n.parse::<u8>().ok())
+        .is_some_and(|n| (1..=25).contains(&n))
+}
+
+fn feature(name: &str) -> bool {
+    env::var_os(format!("CARGO_FEATURE_{}", name.to_uppercase())).is_some()
+}
+
+/// Directories of the days in `src`, sorted.
+fn discover(src: &Path) -> std::io::Result<Vec<(String, PathBuf)>> {
+    let mut days = vec![];
+    for entry in fs::read_dir(src)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if is_day(name) && path.join("mod.rs").is_file() {
+            days.push((name.to_owned(), path));
+        }
+    }
+    days.sort();
+    Ok(days)
+}
+
+/// Named inputs in the `inputs` directory of a day, sorted, as the `aoc!` syntax for them: the name, the path of the
+/// input and the paths of the answers to each part that are known.
+fn named_inputs(dir: &Path) -> std::io::Result<Vec<String>> {
+    let entries = match fs::read_dir(dir.join("inputs")) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+    let mut inputs = vec![];
+    for entry in entries {
+        let path = entry?.path();
+        let Some(name) = path.file_stem().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if path.extension().is_none_or(|ext| ext != "txt") {
+            continue;
+        }
+        let answers = [1, 2].map(|part| {
+            let sidecar = path.with_extension(format!("part{part}"));
+            match sidecar.is_file() {
+                true => format!("[{sidecar:?}]"),
+                false => "[]".to_owned(),
+            }
+        });
+        inputs.push(format!("{name:?} = {path:?} {}", answers.join(" ")));
+    }
+    inputs.sort();
+    Ok(inputs)
+}
+
+fn main() {
+    let root = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default());
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap_or_default());
+    println!("cargo::rerun-if-changed=src");
+
+    let all = (1..=25).map(|day| format!("day{day:02}")).collect::<Vec<_>>();
+    println!("cargo::rustc-check-cfg=cfg({})", all.join(", "));
+
+    let fail = |message: String| -> ! {
+        eprintln!("error: {message}");
+        process::exit(1)
+    };
+    let days = discover(&root.join("src")).unwrap_or_else(|e| fail(format!("failed to list src: {e}")));
+
+    let mut missing = vec![];
+    for (day, dir) in &days {
+        if !feature("all") && !feature(day) {
+            continue;
+        }
+        println!("cargo::rustc-cfg={day}");
+        // `include_str!` would fail on it with an error pointing into `solution!`.
+        if !dir.join("input").is_file() {
+            missing.push(format!("src/{day}/input"));
+        }
+    }
+    if !missing.is_empty() {
+        fail(format!(
+            "missing puzzle input: {} (save the input there, or create an empty file until it is known)",
+            missing.join(", ")
+        ));
+    }
+
+    let mut entries = String::new();
+    for (day, dir) in &days {
+        let inputs = named_inputs(dir).unwrap_or_else(|e| fail(format!("failed to list src/{day}/inputs: {e}")));
+        entries += &format!("    {day} = {:?} [{}],\n", dir.join("mod.rs"), inputs.join(", "));
+    }
+    let generated = format!("// Generated by build.rs from the directories of the days.\ncrate::aoc!(\n{entries});\n");
+    fs::write(out.join("days.rs"), generated).unwrap_or_else(|e| fail(format!("failed to write days.rs: {e}")));
+}
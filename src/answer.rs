@@ -0,0 +1,200 @@
+use std::{
+    convert::Infallible,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Answer to one part of a puzzle.
+///
+/// Numbers compare by value regardless of signedness, and with text as they are written, since an answer is what
+/// gets typed in. Multi-line blocks (e.g. letters drawn in ASCII art) compare line by line, ignoring trailing
+/// whitespace and surrounding blank lines.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Int(u64),
+    Signed(i64),
+    Text(String),
+    Block(String),
+}
+
+/// Conversion of a part's output into an [`Answer`].
+pub trait IntoAnswer {
+    fn into_answer(self) -> Answer;
+}
+
+/// An expected answer for a part whose output is `O`, typically a literal written in `solution!`.
+///
+/// Besides values of `O` itself, string literals are accepted for `String` outputs, and numbers, strings and
+/// characters for [`Answer`] outputs.
+pub trait Expected<O> {
+    fn into_expected(self) -> Answer;
+}
+
+impl<O: IntoAnswer> Expected<O> for O {
+    fn into_expected(self) -> Answer {
+        self.into_answer()
+    }
+}
+
+impl Expected<String> for &str {
+    fn into_expected(self) -> Answer {
+        self.into_answer()
+    }
+}
+
+impl IntoAnswer for Answer {
+    fn into_answer(self) -> Answer {
+        self
+    }
+}
+
+macro_rules! into_answer {
+    ($variant:ident: $($ty:ty),*) => {
+        $(impl IntoAnswer for $ty {
+            fn into_answer(self) -> Answer {
+                Answer::$variant(self as _)
+            }
+        })*
+    };
+}
+
+into_answer!(Int: u8, u16, u32, u64, usize);
+into_answer!(Signed: i8, i16, i32, i64, isize);
+
+macro_rules! expected_answer {
+    ($($ty:ty),*) => {
+        $(impl Expected<Answer> for $ty {
+            fn into_expected(self) -> Answer {
+                self.into_answer()
+            }
+        })*
+    };
+}
+
+expected_answer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, String, &str, char);
+
+impl IntoAnswer for String {
+    fn into_answer(self) -> Answer {
+        match self.contains('\n') {
+            true => Answer::Block(self),
+            false => Answer::Text(self),
+        }
+    }
+}
+
+impl IntoAnswer for &str {
+    fn into_answer(self) -> Answer {
+        self.to_owned().into_answer()
+    }
+}
+
+impl IntoAnswer for char {
+    fn into_answer(self) -> Answer {
+        Answer::Text(self.into())
+    }
+}
+
+/// Parses an answer written down as text, e.g. in a file: integers become numbers, unless they are written in
+/// another way than numbers are printed (e.g. with leading zeros), and text spanning several lines becomes a block.
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Infallible> {
+        let s = s.trim_end_matches(['\n', '\r']);
+        let answer = match (s.parse(), s.parse()) {
+            (Ok(n), _) => Self::Int(n),
+            (_, Ok(n)) => Self::Signed(n),
+            _ => return Ok(s.into_answer()),
+        };
+        Ok(match answer.to_string() == s {
+            true => answer,
+            false => s.into_answer(),
+        })
+    }
+}
+
+impl Answer {
+    fn as_i128(&self) -> Option<i128> {
+        match *self {
+            Self::Int(n) => Some(n.into()),
+            Self::Signed(n) => Some(n.into()),
+            _ => None,
+        }
+    }
+
+    fn block_lines(s: &str) -> impl Iterator<Item = &str> {
+        s.trim_matches('\n').lines().map(str::trim_end)
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Text(a), Self::Text(b)) => a == b,
+            (Self::Block(a), Self::Block(b)) => Self::block_lines(a).eq(Self::block_lines(b)),
+            (Self::Text(s), n) | (n, Self::Text(s)) if n.as_i128().is_some() => *s == n.to_string(),
+            _ => matches!((self.as_i128(), other.as_i128()), (Some(a), Some(b)) if a == b),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Int(n) => f.pad(&n.to_string()),
+            Self::Signed(n) => f.pad(&n.to_string()),
+            Self::Text(s) => f.pad(s),
+            Self::Block(s) => f.write_str(s.trim_matches('\n')),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        assert!(matches!(42usize.into_answer(), Answer::Int(42)));
+        assert!(matches!((-7i32).into_answer(), Answer::Signed(-7)));
+        assert!(matches!("abc".into_answer(), Answer::Text(s) if s == "abc"));
+        assert!(matches!("#.\n.#".into_answer(), Answer::Block(_)));
+    }
+
+    #[test]
+    fn equality() {
+        assert_eq!(42u64.into_answer(), 42i32.into_answer());
+        assert_ne!(42u64.into_answer(), (-42i64).into_answer());
+        assert_ne!(u64::MAX.into_answer(), (-1i64).into_answer());
+        assert_eq!(42.into_answer(), "42".into_answer());
+        assert_eq!("-7".into_answer(), (-7i8).into_answer());
+        assert_ne!(42.into_answer(), "042".into_answer());
+        assert_ne!(42.into_answer(), "42.0".into_answer());
+        assert_eq!("#..\n.#.\n".into_answer(), "\n#..  \n.#.".into_answer());
+        assert_ne!("#..\n.#.".into_answer(), "#..\n..#".into_answer());
+    }
+
+    #[test]
+    fn parse() {
+        let parse = |s: &str| s.parse::<Answer>().ok();
+        assert!(matches!(parse("42\n"), Some(Answer::Int(42))));
+        assert!(matches!(parse("-42"), Some(Answer::Signed(-42))));
+        assert!(matches!(parse("abc\n"), Some(Answer::Text(s)) if s == "abc"));
+        assert!(matches!(parse("007\n"), Some(Answer::Text(s)) if s == "007"));
+        assert!(matches!(parse("+5"), Some(Answer::Text(s)) if s == "+5"));
+        assert_eq!(parse("007"), Some("007".into_answer()));
+        assert_ne!(parse("007"), Some(7.into_answer()));
+        assert_eq!(parse("42"), Some("42".into_answer()));
+        assert!(matches!(parse("#.\n.#\n"), Some(Answer::Block(s)) if s == "#.\n.#"));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(format!("{:5}|", 42.into_answer()), "42   |");
+        assert_eq!(format!("{:5}|", "ab".into_answer()), "ab   |");
+        assert_eq!((-3).into_answer().to_string(), "-3");
+        assert_eq!("\n#.\n.#\n".into_answer().to_string(), "#.\n.#");
+    }
+}
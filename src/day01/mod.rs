@@ -54,7 +54,27 @@ fn find_digit(line: &str, reverse: bool) -> Option<usize> {
     None
 }
 
-solution!(part1 => 54331, part2 => 54518);
+/// `size` lines of letters, digits and spelled-out digits, with at least one digit each.
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for _ in 0..size {
+        let mut line = String::new();
+        for _ in 0..rng.between(1, 6) {
+            match rng.below(3) {
+                0 => line.push(rng.pick(b"0123456789") as char),
+                1 => line.push_str(&String::from_utf8_lossy(rng.pick(DIGIT_WORDS))),
+                _ => line.extend((0..rng.between(1, 5)).map(|_| (b'a' + rng.below(26) as u8) as char)),
+            }
+        }
+        let at = rng.below(line.len() + 1);
+        line.insert(at, rng.pick(b"123456789") as char);
+        input.push_str(&line);
+        input.push('\n');
+    }
+    input
+}
+
+solution!(title: "Trebuchet?!", part1 => 54331, part2 => 54518, generate: generate);
 
 #[cfg(test)]
 mod tests {
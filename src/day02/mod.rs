@@ -1,4 +1,4 @@
-use crate::*;
+use crate::{parse::*, *};
 
 struct Cubes {
     r: usize,
@@ -11,70 +11,58 @@ struct Game {
     sets:  Vec<Cubes>,
 }
 
-impl FromStr for Cubes {
-    type Err = anyhow::Error;
-
-    fn from_str(value: &str) -> Result<Self, Self::Err> {
-        let (mut r, mut g, mut b) = (0, 0, 0);
-        for s in value.split(',') {
-            let mut it = s.trim().split(' ');
-            let (n, color) = match (it.next(), it.next(), it.next()) {
-                (Some(n), Some(color), None) => (n.parse::<usize>()?, color),
-                _ => bail!("Invalid cube: {s}"),
-            };
-            match color {
-                "red" => r += n,
-                "green" => g += n,
-                "blue" => b += n,
-                _ => bail!("Invalid cube: {s}"),
-            }
+fn parse_games(input: &str) -> Result<Vec<Game>> {
+    const COLORS: &[(&str, usize)] = &[("red", 0), ("green", 1), ("blue", 2)];
+    let cube = pair(terminated(uint::<usize>(), spaces()), one_of(COLORS, "a colour"));
+    let set = separated(cube, literal(", ")).map(|cubes| {
+        let mut counts = [0; 3];
+        for (n, color) in cubes {
+            counts[color] += n;
         }
-        Ok(Self { r, g, b })
-    }
+        let [r, g, b] = counts;
+        Cubes { r, g, b }
+    });
+    let game = pair(header("Game", uint()), separated(set, literal("; "))).map(|(round, sets)| Game { round, sets });
+    parse_all(input, lines(game))
 }
 
-impl FromStr for Game {
-    type Err = anyhow::Error;
-
-    fn from_str(value: &str) -> Result<Self, Self::Err> {
-        let (round, sets) = value.split_once(": ").ok_or_else(|| anyhow!("Invalid game: {value}"))?;
-        let round = round
-            .rsplit(' ')
-            .next()
-            .ok_or_else(|| anyhow!("Invalid game: {value}"))?
-            .parse()?;
-        let sets = sets.split(';').map(|s| s.parse()).try_collect()?;
-        Ok(Self { round, sets })
-    }
+fn part1(games: &[Game]) -> Result<usize> {
+    Ok(games
+        .iter()
+        .filter(|game| game.sets.iter().all(|&Cubes { r, g, b }| r <= 12 && g <= 13 && b <= 14))
+        .map(|game| game.round)
+        .sum())
 }
 
-fn part1(input: &str) -> Result<usize> {
-    input.lines().try_fold(0, |mut acc, line| {
-        Game::from_str(line).map(|game| {
-            if game.sets.iter().all(|&Cubes { r, g, b }| r <= 12 && g <= 13 && b <= 14) {
-                acc += game.round;
-            }
-            acc
+fn part2(games: &[Game]) -> Result<usize> {
+    Ok(games
+        .iter()
+        .map(|game| {
+            game.sets
+                .iter()
+                .fold([0, 0, 0], |[r, g, b], s| [max(r, s.r), max(g, s.g), max(b, s.b)])
+                .iter()
+                .product::<usize>()
         })
-    })
+        .sum())
 }
 
-fn part2(input: &str) -> Result<usize> {
+/// `size` games of a few draws each.
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for game in 1..=size {
+        let sets = (0..rng.between(1, 6)).map(|_| {
+            let mut colors = ["red", "green", "blue"];
+            rng.shuffle(&mut colors);
+            let n = rng.between(1, 3);
+            colors[..n].iter().map(|color| format!("{} {color}", rng.between(1, 20))).join(", ")
+        });
+        input.push_str(&format!("Game {game}: {}\n", sets.format("; ")));
+    }
     input
-        .lines()
-        .map(|line| {
-            Game::from_str(line).map(|game| {
-                game.sets
-                    .iter()
-                    .fold([0, 0, 0], |[r, g, b], s| [max(r, s.r), max(g, s.g), max(b, s.b)])
-                    .iter()
-                    .product::<usize>()
-            })
-        })
-        .sum()
 }
 
-solution!(part1 => 2204, part2 => 71036);
+solution!(title: "Cube Conundrum", parse: parse_games, part1 => 2204, part2 => 71036, generate: generate);
 
 #[cfg(test)]
 mod tests {
@@ -86,6 +74,6 @@ mod tests {
         Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
     "};
 
-    crate::test!(part1, t1: EXAMPLE => 8);
+    crate::test!(part1, t1: EXAMPLE => 8, crlf: &EXAMPLE.replace('\n', "\r\n") => 8);
     crate::test!(part2, t1: EXAMPLE => 2286);
 }
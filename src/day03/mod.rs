@@ -1,112 +1,109 @@
 use crate::*;
 
-struct Grid<'a> {
-    data:  Vec<&'a [u8]>,
-    nrows: usize,
-    ncols: usize,
+fn is_symbol(c: u8) -> bool {
+    !c.is_ascii_digit() && c != b'.'
 }
 
-#[rustfmt::skip]
-const ADJS: &[(isize, isize)] = &[
-    (-1, -1), (-1, 0), (-1, 1),
-    ( 0, -1),          ( 0, 1),
-    ( 1, -1), ( 1, 0), ( 1, 1),
-];
-
-impl<'a> Grid<'a> {
-    fn new(input: &'a str) -> Result<Self> {
-        let data = input.lines().map(|line| line.as_bytes()).collect_vec();
-        let nrows = data.len();
-        let ncols = match data.iter().map(|row| row.len()).all_equal_value() {
-            Ok(ncols) => ncols,
-            _ => bail!("invalid input"),
-        };
-        Ok(Self { data, nrows, ncols })
-    }
-
-    fn get(&self, row: usize, col: usize) -> Option<u8> {
-        self.data.get(row).and_then(|row| row.get(col)).copied()
-    }
+fn adjacent_to_symbol(grid: &Grid<u8>, pos: Point) -> bool {
+    grid.neighbours8(pos).any(|pos| grid.get(pos).is_some_and(|&c| is_symbol(c)))
+}
 
-    fn adjacent(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> {
-        ADJS.iter()
-            .map(move |&(i, j)| (row.wrapping_add_signed(i), col.wrapping_add_signed(j)))
-    }
+/// The number made of `digits`, if it fits.
+fn number(digits: &[u8]) -> Result<usize> {
+    digits
+        .iter()
+        .try_fold(0usize, |acc, &c| acc.checked_mul(10)?.checked_add((c - b'0') as usize))
+        .ok_or_else(|| anyhow!("number too large: {}", String::from_utf8_lossy(digits)))
+}
 
-    fn is_symbol(&self, row: usize, col: usize) -> bool {
-        matches!(self.get(row, col), Some(c) if !c.is_ascii_digit() && c != b'.')
-    }
+/// The digits of the number with a digit at `pos`, and the position of its first digit.
+fn num_at(grid: &Grid<u8>, pos: Point) -> Option<(Point, &[u8])> {
+    let line = grid.row(pos.row)?;
+    line.get(pos.col).filter(|c| c.is_ascii_digit()).map(|_| {
+        let col = match line[..pos.col].iter().rev().position(|c| !c.is_ascii_digit()) {
+            Some(n) => pos.col - n,
+            None => 0,
+        };
+        let len = line[col..].iter().take_while(|c| c.is_ascii_digit()).count();
+        (Point::new(pos.row, col), &line[col..col + len])
+    })
+}
 
-    fn adjacent_to_symbol(&self, row: usize, col: usize) -> bool {
-        self.adjacent(row, col).any(|(i, j)| self.is_symbol(i, j))
+fn gear_ratio(grid: &Grid<u8>, pos: Point) -> Result<Option<usize>> {
+    if grid.get(pos) != Some(&b'*') {
+        return Ok(None);
     }
-
-    fn num_at(&self, row: usize, col: usize) -> Option<(usize, usize)> {
-        self.get(row, col).filter(u8::is_ascii_digit).map(|_| {
-            let col = match self.data[row][..col].iter().rev().position(|c| !c.is_ascii_digit()) {
-                Some(n) => col - n,
-                None => 0,
-            };
-            let num = self.data[row][col..]
-                .iter()
-                .take_while(|c| c.is_ascii_digit())
-                .fold(0, |acc, &c| acc * 10 + (c - b'0') as usize);
-            (row * self.ncols + col, num) // (position, number)
-        })
+    let nums: HashMap<_, _> = grid.neighbours8(pos).filter_map(|pos| num_at(grid, pos)).collect();
+    if nums.len() != 2 {
+        return Ok(None);
     }
-
-    fn gear_ratio(&self, row: usize, col: usize) -> Option<usize> {
-        self.get(row, col).filter(|&c| c == b'*').and_then(|_| {
-            let nums: HashMap<_, _> = self.adjacent(row, col).filter_map(|(i, j)| self.num_at(i, j)).collect();
-            match nums.len() {
-                2 => Some(nums.values().product()),
-                _ => None,
-            }
-        })
+    let mut ratio = 1usize;
+    for digits in nums.values() {
+        ratio = ratio.checked_mul(number(digits)?).ok_or_else(|| anyhow!("gear ratio too large at ({}, {})", pos.row, pos.col))?;
     }
+    Ok(Some(ratio))
 }
 
 fn part1(input: &str) -> Result<usize> {
-    let grid = Grid::new(input)?;
+    let grid = Grid::parse(input)?;
 
-    let mut sum = 0;
-    for i in 0..grid.nrows {
-        let mut num = 0;
+    let mut sum = 0usize;
+    for (i, row) in grid.rows().enumerate() {
+        let mut start = 0;
         let mut is_part_num = false;
-        for j in 0..grid.ncols {
-            let c = grid.data[i][j];
+        for (j, &c) in row.iter().chain([&b'.']).enumerate() {
             if c.is_ascii_digit() {
-                num = num * 10 + (c - b'0') as usize;
-                is_part_num |= grid.adjacent_to_symbol(i, j);
+                is_part_num |= adjacent_to_symbol(&grid, Point::new(i, j));
             } else {
                 if is_part_num {
-                    sum += num;
+                    sum = sum.checked_add(number(&row[start..j])?).ok_or_else(|| anyhow!("sum too large"))?;
                 }
-                num = 0;
+                start = j + 1;
                 is_part_num = false;
             }
         }
-
-        if is_part_num {
-            sum += num;
-        }
     }
 
     Ok(sum)
 }
 
 fn part2(input: &str) -> Result<usize> {
-    let grid = Grid::new(input)?;
+    let grid = Grid::parse(input)?;
 
-    let sum = (0..grid.nrows)
-        .flat_map(|i| (0..grid.ncols).map(move |j| (i, j)))
-        .filter_map(|(i, j)| grid.gear_ratio(i, j))
-        .sum();
+    let mut sum = 0usize;
+    for pos in grid.positions() {
+        if let Some(ratio) = gear_ratio(&grid, pos)? {
+            sum = sum.checked_add(ratio).ok_or_else(|| anyhow!("sum too large"))?;
+        }
+    }
 
     Ok(sum)
 }
 
-solution!(part1 => 529618, part2 => 77509019);
+/// A square schematic of about `size` tiles, with scattered numbers and symbols.
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let side = size.isqrt().max(1);
+    let mut input = String::with_capacity((side + 1) * side);
+    for _ in 0..side {
+        let mut row = String::with_capacity(side);
+        while row.len() < side {
+            match rng.below(10) {
+                // A number, never right after another one.
+                0 | 1 if !row.ends_with(|c: char| c.is_ascii_digit()) => {
+                    let digits = min(rng.between(1, 3), side - row.len());
+                    row.extend((0..digits).map(|_| rng.pick(b"0123456789") as char));
+                }
+                2 => row.push(rng.pick(b"*#+$/@=%-&") as char),
+                _ => row.push('.'),
+            }
+        }
+        input.push_str(&row);
+        input.push('\n');
+    }
+    input
+}
+
+solution!(title: "Gear Ratios", part1 => 529618, part2 => 77509019, generate: generate);
 
 #[cfg(test)]
 mod tests {
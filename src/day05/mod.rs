@@ -1,165 +1,108 @@
 use crate::*;
 
-#[derive(Debug)]
-struct MapEntry {
-    range: Range<usize>,
-    delta: isize,
-}
-
-impl MapEntry {
-    fn new(src: usize, dst: usize, len: usize) -> Self {
-        Self { range: src..src + len, delta: dst as isize - src as isize }
-    }
-
-    // TODO: remove this
-    fn mapped(&self, key: usize) -> Option<usize> {
-        self.range.contains(&key).then(|| key.wrapping_add_signed(self.delta))
-    }
-}
-
-struct Map {
-    entries: Vec<MapEntry>,
-}
-
-impl Map {
-    fn new(mut entries: Vec<MapEntry>) -> Self {
-        entries.sort_unstable_by_key(|e| e.range.start);
-        Self { entries }
-    }
-
-    fn mapped(&self, key: usize) -> usize {
-        self.entries
-            .binary_search_by(|MapEntry { range: Range { start, end }, .. }| match key {
-                k if start > &k => Ordering::Greater,
-                k if end <= &k => Ordering::Less,
-                _ => Ordering::Equal,
-            })
-            .ok()
-            .and_then(|i| self.entries[i].mapped(key))
-            .unwrap_or(key)
-    }
-
-    fn mapped_ranges(&self, keys: Range<usize>) -> Vec<Range<usize>> {
-        let (mut kstart, kend) = (keys.start, keys.end);
-        let mut ranges = Vec::new();
-
-        let mut i = match self
-            .entries
-            .binary_search_by(|MapEntry { range: Range { start, end }, .. }| match kstart {
-                kstart if start > &kstart => Ordering::Greater,
-                kstart if end <= &kstart => Ordering::Less,
-                _ => Ordering::Equal,
-            }) {
-            Ok(i) => i,
-            Err(i) => match self.entries.get(i) {
-                Some(entry) => {
-                    let len = min(entry.range.start, kend) - kstart;
-                    ranges.push(kstart..kstart + len);
-                    kstart += len;
-                    i
-                }
-                None => {
-                    ranges.push(kstart..kend);
-                    return ranges;
-                }
-            },
-        };
-
-        while i < self.entries.len() && kstart < kend {
-            let entry = &self.entries[i];
-            let vstart = entry.mapped(kstart).expect("invalid state");
-            let len = min(entry.range.end, kend) - kstart;
-            ranges.push(vstart..vstart + len);
-            kstart += len;
-
-            i += 1;
-
-            let len = match self.entries.get(i) {
-                Some(entry) => min(entry.range.start, kend) - kstart,
-                None => kend - kstart,
-            };
-            ranges.push(kstart..kstart + len);
-            kstart += len;
-        }
-
-        ranges.into_iter().filter(|range| !range.is_empty()).collect()
-    }
-}
-
-struct MapChain {
-    maps: Vec<Map>,
-}
-
-impl MapChain {
-    fn mapped(&self, key: usize) -> usize {
-        self.maps.iter().fold(key, |key, map| map.mapped(key))
-    }
-
-    fn mapped_ranges(&self, range: Range<usize>) -> Vec<Range<usize>> {
-        self.maps.iter().fold(vec![range], |ranges, map| {
-            ranges.into_iter().flat_map(|range| map.mapped_ranges(range)).collect()
-        })
+/// A line `dst src len` of a map.
+fn parse_piece(line: &str) -> Result<(Range<usize>, usize)> {
+    let mut parts = line.split_ascii_whitespace();
+    let (Some(dst), Some(src), Some(len), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+        bail!("invalid line: {}", line)
+    };
+    let (dst, src, len): (usize, usize, usize) = (dst.parse()?, src.parse()?, len.parse()?);
+    match src.checked_add(len) {
+        Some(end) => Ok((src..end, dst)),
+        None => bail!("range too large: {}", line),
     }
 }
 
-fn parse_map_chain(input: &str) -> Result<MapChain> {
+fn parse_maps(input: &str) -> Result<Vec<PiecewiseMap>> {
     input
         .split("\n\n")
-        .try_fold(MapChain { maps: Vec::new() }, |mut map_chain, part| {
+        .map(|part| {
             let lines = &mut part.lines();
             match lines.next() {
                 Some(line) if line.ends_with("map:") => {
-                    let entries = lines
-                        .map(|line| {
-                            let mut parts = line.split_ascii_whitespace();
-                            match (parts.next(), parts.next(), parts.next(), parts.next()) {
-                                (Some(dst), Some(src), Some(len), None) =>
-                                    Ok(MapEntry::new(src.parse()?, dst.parse()?, len.parse()?)),
-                                _ => bail!("invalid line: {}", line),
-                            }
-                        })
-                        .try_collect()?;
-                    map_chain.maps.push(Map::new(entries));
-                    Ok(map_chain)
+                    let pieces: Vec<_> = lines.map(parse_piece).try_collect()?;
+                    PiecewiseMap::new(pieces)
                 }
                 _ => bail!("invalid part: {}", part),
             }
         })
+        .try_collect()
 }
 
-fn parse_seeds(input: &str) -> Result<impl Iterator<Item = Result<usize, std::num::ParseIntError>> + '_> {
+fn parse_seeds(input: &str) -> Result<Vec<usize>> {
     match input.strip_prefix("seeds:") {
-        Some(seeds) => Ok(seeds.split_ascii_whitespace().map(|s| s.parse())),
+        Some(seeds) => Ok(seeds.split_ascii_whitespace().map(|s| s.parse()).try_collect()?),
         None => bail!("invalid seeds: {}", input),
     }
 }
 
-fn part1(input: &str) -> Result<usize> {
-    let (seeds, map_chain) = input.split_once("\n\n").ok_or_else(|| anyhow!("invalid input"))?;
-    let map_chain = parse_map_chain(map_chain)?;
+struct Almanac {
+    seeds: Vec<usize>,
+    maps:  Vec<PiecewiseMap>,
+}
 
-    let mut min_loc = usize::MAX;
-    for seed in parse_seeds(seeds)? {
-        min_loc = min(min_loc, map_chain.mapped(seed?));
-    }
-    Ok(min_loc)
+fn parse_almanac(input: &str) -> Result<Almanac> {
+    let (seeds, maps) = input.split_once("\n\n").ok_or_else(|| anyhow!("invalid input"))?;
+    Ok(Almanac { seeds: parse_seeds(seeds)?, maps: parse_maps(maps)? })
+}
+
+fn part1(Almanac { seeds, maps }: &Almanac) -> Result<usize> {
+    seeds
+        .iter()
+        .map(|&seed| maps.iter().fold(seed, |key, map| map.get(key)))
+        .min()
+        .ok_or_else(|| anyhow!("no seeds"))
 }
 
-fn part2(input: &str) -> Result<usize> {
-    let (seeds, map_chain) = input.split_once("\n\n").ok_or_else(|| anyhow!("invalid input"))?;
-    let map_chain = parse_map_chain(map_chain)?;
+fn part2(Almanac { seeds, maps }: &Almanac) -> Result<usize> {
+    let seeds: RangeSet = seeds
+        .iter()
+        .copied()
+        .tuples()
+        .map(|(start, count)| match start.checked_add(count) {
+            Some(end) => Ok(start..end),
+            None => bail!("seed range too large: {} {}", start, count),
+        })
+        .try_collect()?;
+    let locations = maps.iter().fold(seeds, |keys, map| map.apply(&keys));
+    locations.min().ok_or_else(|| anyhow!("no seeds"))
+}
 
-    let mut min_loc = usize::MAX;
-    for [start, count] in parse_seeds(seeds)?.array_chunks() {
-        let (start, count) = (start?, count?);
-        for range in map_chain.mapped_ranges(start..start + count) {
-            min_loc = min(min_loc, range.start);
+/// An almanac of `size` map entries spread over the seven maps, and a tenth as many seed ranges.
+fn generate(size: usize, rng: &mut Rng) -> String {
+    const MAPS: [&str; 7] = [
+        "seed-to-soil",
+        "soil-to-fertilizer",
+        "fertilizer-to-water",
+        "water-to-light",
+        "light-to-temperature",
+        "temperature-to-humidity",
+        "humidity-to-location",
+    ];
+    const SPACE: usize = 1 << 32;
+
+    let seeds = (0..(size / 10).max(1)).map(|_| format!("{} {}", rng.below(SPACE), rng.between(1, 1 << 28)));
+    let mut input = format!("seeds: {}\n", seeds.format(" "));
+    for (i, name) in MAPS.iter().enumerate() {
+        // Entries cover consecutive ranges of sources, with gaps left unmapped.
+        let entries = size / MAPS.len() + usize::from(i < size % MAPS.len());
+        let mut bounds = (0..2 * entries).map(|_| rng.below(SPACE)).collect_vec();
+        bounds.sort_unstable();
+        input.push_str(&format!("\n{name} map:\n"));
+        for (start, end) in bounds.into_iter().tuples() {
+            input.push_str(&format!("{} {} {}\n", rng.below(SPACE), start, end - start));
         }
     }
-    Ok(min_loc)
+    input
 }
 
-solution!(part1 => 535088217, part2 => 51399228);
+solution!(
+    title: "If You Give A Seed A Fertilizer",
+    parse: parse_almanac,
+    part1 => 535088217,
+    part2 => 51399228,
+    generate: generate,
+);
 
 #[cfg(test)]
 mod tests {
@@ -16,32 +16,29 @@ fn part1(input: &str) -> Result<usize> {
         .map(|s| s.parse())
         .try_collect()?;
 
-    let res = time
-        .into_iter()
+    time.into_iter()
         .zip(dis)
-        .map(|(t, d)| succeed_ways(t, d))
-        .product::<usize>();
-
-    Ok(res)
+        .try_fold(1usize, |acc, (t, d)| acc.checked_mul(succeed_ways(t, d)))
+        .ok_or_else(|| anyhow!("too many ways to win"))
 }
 
 fn part2(input: &str) -> Result<usize> {
     let (time, dis) = input.split_once('\n').ok_or_else(|| anyhow!("invalid input"))?;
 
-    let time: usize = time
-        .strip_prefix("Time:")
-        .ok_or_else(|| anyhow!("invalid time: {}", time))?
-        .bytes()
-        .filter(|c| c.is_ascii_digit())
-        .fold(0, |acc, c| acc * 10 + (c - b'0') as usize);
-    let dis: usize = dis
+    let time = time.strip_prefix("Time:").ok_or_else(|| anyhow!("invalid time: {}", time))?;
+    let dis = dis
         .strip_prefix("Distance:")
-        .ok_or_else(|| anyhow!("invalid distance: {}", dis))?
-        .bytes()
-        .filter(|c| c.is_ascii_digit())
-        .fold(0, |acc, c| acc * 10 + (c - b'0') as usize);
+        .ok_or_else(|| anyhow!("invalid distance: {}", dis))?;
+
+    Ok(succeed_ways(join_digits(time)?, join_digits(dis)?))
+}
 
-    Ok(succeed_ways(time, dis))
+/// Reads the digits of `s` as a single number, ignoring the spaces between them.
+fn join_digits(s: &str) -> Result<usize> {
+    s.bytes()
+        .filter(|c| c.is_ascii_digit())
+        .try_fold(0usize, |acc, c| acc.checked_mul(10)?.checked_add((c - b'0') as usize))
+        .ok_or_else(|| anyhow!("number too large: {}", s.trim()))
 }
 
 fn succeed_ways(time: usize, dis: usize) -> usize {
@@ -49,14 +46,18 @@ fn succeed_ways(time: usize, dis: usize) -> usize {
     // => x1 = (t - sqrt(t^2 - 4(d + 1))) / 2
     //    x2 = (t + sqrt(t^2 - 4(d + 1))) / 2
     let (t, d) = (time as f64, dis as f64);
-    let t1 = (t - (t * t - 4.0 * (d + 1.0)).sqrt()) / 2.0;
-    let t2 = (t + (t * t - 4.0 * (d + 1.0)).sqrt()) / 2.0;
+    let delta = t * t - 4.0 * (d + 1.0);
+    if delta < 0.0 {
+        return 0;
+    }
+    let t1 = (t - delta.sqrt()) / 2.0;
+    let t2 = (t + delta.sqrt()) / 2.0;
     let t1 = t1.ceil() as usize;
     let t2 = t2.floor() as usize;
-    t2 - t1 + 1
+    t2.checked_sub(t1).map_or(0, |n| n + 1)
 }
 
-solution!(part1 => 449550, part2 => 28360140);
+solution!(title: "Wait For It", part1 => 449550, part2 => 28360140);
 
 #[cfg(test)]
 mod tests {
@@ -2,6 +2,7 @@ use crate::*;
 
 #[rustfmt::skip]
 fn compute_rank(s: &str, jocker: bool) -> Result<u32> {
+    ensure!(s.chars().count() == 5, "Invalid hand: {}", s);
     let mut cards = [0; 5];
     for (i, c) in s.chars().enumerate() {
         cards[i] = match c {
@@ -43,7 +44,11 @@ fn solve(input: &str, jocker: bool) -> Result<usize> {
         })
         .try_collect()?;
     hands.sort_unstable();
-    Ok(hands.into_iter().zip(1..).map(|((_, bid), i)| bid * i).sum())
+    hands
+        .into_iter()
+        .zip(1usize..)
+        .try_fold(0usize, |acc, ((_, bid), i)| acc.checked_add(bid.checked_mul(i)?))
+        .ok_or_else(|| anyhow!("total winnings too large"))
 }
 
 fn part1(input: &str) -> Result<usize> {
@@ -54,7 +59,17 @@ fn part2(input: &str) -> Result<usize> {
     solve(input, true)
 }
 
-solution!(part1 => 251927063, part2 => 255632664);
+/// `size` random hands and bids.
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for _ in 0..size {
+        let hand: String = (0..5).map(|_| rng.pick(b"23456789TJQKA") as char).collect();
+        input.push_str(&format!("{hand} {}\n", rng.between(1, 1000)));
+    }
+    input
+}
+
+solution!(title: "Camel Cards", part1 => 251927063, part2 => 255632664, generate: generate);
 
 #[cfg(test)]
 mod tests {
@@ -4,86 +4,125 @@ fn name_to_id(name: &str) -> u32 {
     name.bytes().fold(0, |acc, c| acc * 26 + (c - b'A') as u32)
 }
 
+fn id_to_name(id: u32) -> String {
+    [id / 676, id / 26 % 26, id % 26].map(|c| (b'A' + c as u8) as char).iter().collect()
+}
+
 type Network = HashMap<u32, (u32, u32)>;
 
-fn parse_input(input: &str) -> Result<(&str, Network)> {
+fn parse_input(input: &str) -> Result<(String, Network)> {
     let (dirs, nodes) = input.split_once("\n\n").ok_or_else(|| anyhow!("invalid input"))?;
+    ensure!(!dirs.is_empty(), "no directions");
 
     let network: Network = nodes
         .lines()
         .map(|line| {
-            let mut it = line
-                .split(|c: char| !c.is_ascii_uppercase())
-                .filter(|s| !s.is_empty())
-                .map(name_to_id);
+            let mut it = line.split(|c: char| !c.is_ascii_uppercase()).filter(|s| !s.is_empty());
             match (it.next(), it.next(), it.next(), it.next()) {
-                (Some(name), Some(left), Some(right), None) => Ok((name, (left, right))),
+                (Some(name), Some(left), Some(right), None) if [name, left, right].iter().all(|n| n.len() == 3) => {
+                    Ok((name_to_id(name), (name_to_id(left), name_to_id(right))))
+                }
                 _ => bail!("invalid node: {}", line),
             }
         })
         .try_collect()?;
-    Ok((dirs, network))
+    Ok((dirs.to_owned(), network))
 }
 
-fn part1(input: &str) -> Result<usize> {
-    let (dirs, network) = parse_input(input)?;
-
-    let dest = name_to_id("ZZZ");
-    let mut curr = name_to_id("AAA");
+/// Number of steps from `start` to the first node for which `is_end` holds.
+fn steps(dirs: &str, network: &Network, start: u32, is_end: impl Fn(u32) -> bool) -> Result<usize> {
+    // Past this many steps, some node has been reached twice at the same point of the directions: the walk cycles.
+    let limit = dirs.len() * network.len();
+    let mut curr = start;
 
-    for (dir, i) in dirs.chars().cycle().zip(1..) {
-        let (left, right) = network[&curr];
+    for (dir, i) in dirs.chars().cycle().zip(1..=limit) {
+        let &(left, right) = network
+            .get(&curr)
+            .ok_or_else(|| anyhow!("unknown node: {}", id_to_name(curr)))?;
         curr = match dir {
             'L' => left,
             'R' => right,
             _ => bail!("invalid direction: {}", dir),
         };
 
-        if curr == dest {
+        if is_end(curr) {
             return Ok(i);
         }
     }
 
-    unreachable!()
+    bail!("no end is reachable from {}", id_to_name(start))
 }
 
-fn part2(input: &str) -> Result<usize> {
-    let (dirs, network) = parse_input(input)?;
+fn part1((dirs, network): &(String, Network)) -> Result<usize> {
+    let dest = name_to_id("ZZZ");
+    steps(dirs, network, name_to_id("AAA"), |id| id == dest)
+}
 
-    let mut currs: Vec<_> = network.keys().copied().filter(|&id| id % 26 == 0).collect();
-    let mut steps = Vec::with_capacity(currs.len());
+fn part2((dirs, network): &(String, Network)) -> Result<usize> {
+    let counts: Vec<_> = network
+        .keys()
+        .filter(|&id| id % 26 == 0)
+        .map(|&start| steps(dirs, network, start, |id| id % 26 == 25))
+        .try_collect()?;
+    ensure!(!counts.is_empty(), "no start node");
 
-    for curr in currs.iter_mut() {
-        for (dir, i) in dirs.chars().cycle().zip(1..) {
-            let (left, right) = network[curr];
-            *curr = match dir {
-                'L' => left,
-                'R' => right,
-                _ => bail!("invalid direction: {}", dir),
-            };
+    math::lcm_all(&counts)
+}
 
-            if *curr % 26 == 25 {
-                steps.push(i);
-                break;
-            }
+/// A network of about `size` nodes, or as many as there are names for. Like the puzzle's, it is made of one path
+/// per ghost, from a start node to an end node, whose length is the number of directions times a prime: the other
+/// branch of each node leads anywhere, since it is never taken.
+fn generate(size: usize, rng: &mut Rng) -> String {
+    const PRIMES: [usize; 6] = [2, 3, 5, 7, 11, 13];
+    // Names of the nodes along the paths cannot end with `A` or `Z`.
+    let middles = 26 * 26 * 24;
+    let dirs_len = (size / PRIMES.iter().sum::<usize>()).clamp(1, middles / PRIMES.iter().sum::<usize>());
+    let dirs: String = (0..dirs_len).map(|_| rng.pick(b"LR") as char).collect();
+
+    // `AAA` and `ZZZ` belong to the first ghost, the others start and end with two random letters.
+    let mut prefixes = (1..26 * 26 - 1).collect_vec();
+    rng.shuffle(&mut prefixes);
+    let ghosts = PRIMES.len() - 1;
+    let starts = [0].into_iter().chain(prefixes[..ghosts].iter().map(|id| id * 26));
+    let ends = [26 * 26 * 26 - 1].into_iter().chain(prefixes[ghosts..2 * ghosts].iter().map(|id| id * 26 + 25));
+    let mut middles = (0..26 * 26 * 26).filter(|id| !matches!(id % 26, 0 | 25)).collect_vec();
+    rng.shuffle(&mut middles);
+    let mut middles = middles.into_iter();
+
+    let mut nodes = vec![];
+    for ((&prime, start), end) in PRIMES.iter().zip(starts).zip(ends) {
+        let path = [start]
+            .into_iter()
+            .chain(middles.by_ref().take(dirs_len * prime - 1))
+            .chain([end])
+            .collect_vec();
+        for (i, (node, next)) in path.iter().tuple_windows().enumerate() {
+            let other = path[rng.below(path.len())];
+            let (left, right) = match dirs.as_bytes()[i % dirs_len] {
+                b'L' => (*next, other),
+                _ => (other, *next),
+            };
+            nodes.push((*node, left, right));
         }
+        // Past the end, ghosts go around the path again.
+        nodes.push((end, path[1], path[1]));
     }
+    rng.shuffle(&mut nodes);
 
-    Ok(lcm(&steps))
-}
-
-fn lcm(s: &[usize]) -> usize {
-    s.iter().fold(1, |lcm, &x| lcm * x / gcd(lcm, x))
-}
-
-fn gcd(mut a: usize, mut b: usize) -> usize {
-    while b != 0 {
-        (b, a) = (a % b, b);
-    }
-    a
+    let nodes = nodes.into_iter().map(|(node, left, right)| {
+        let [node, left, right] = [node, left, right].map(|id| id_to_name(id as u32));
+        format!("{node} = ({left}, {right})")
+    });
+    format!("{dirs}\n\n{}\n", nodes.format("\n"))
 }
 
-solution!(part1 => 21251, part2 => 11678319315857);
+solution!(
+    title: "Haunted Wasteland",
+    parse: parse_input,
+    part1 => 21251,
+    part2 => 11678319315857,
+    generate: generate,
+);
 
 #[cfg(test)]
 mod tests {
@@ -1,39 +1,58 @@
 use crate::*;
 
-fn part1(input: &str) -> Result<usize> {
-    solve(input, false)
+fn parse_histories(input: &str) -> Result<Vec<Vec<i32>>> {
+    input
+        .lines()
+        .map(|line| line.split_ascii_whitespace().map(|n| n.parse()).try_collect())
+        .try_collect()
+        .map_err(Into::into)
 }
 
-fn part2(input: &str) -> Result<usize> {
-    solve(input, true)
+fn part1(histories: &[Vec<i32>]) -> Result<usize> {
+    solve(histories, false)
 }
 
-fn solve(input: &str, reverse: bool) -> Result<usize> {
-    input
-        .lines()
-        .map(|line| {
-            let iter = line.split_ascii_whitespace().map(|n| n.parse());
-            let history: Vec<_> = match reverse {
-                true => iter.rev().try_collect()?,
-                false => iter.try_collect()?,
-            };
-            predict(&history)
+fn part2(histories: &[Vec<i32>]) -> Result<usize> {
+    solve(histories, true)
+}
+
+fn solve(histories: &[Vec<i32>], reverse: bool) -> Result<usize> {
+    histories
+        .iter()
+        .map(|history| match reverse {
+            true => predict(&history.iter().rev().copied().collect_vec()),
+            false => predict(history),
         })
-        .sum::<Result<i32>>()
+        .try_fold(0i32, |acc, x| acc.checked_add(x?).ok_or_else(|| anyhow!("sum of predictions overflows")))
         .and_then(|x| usize::try_from(x).map_err(Into::into))
 }
 
 fn predict(history: &[i32]) -> Result<i32> {
-    let diff = history.array_windows().map(|[a, b]| b - a).collect_vec();
+    let overflow = || anyhow!("prediction overflows: {:?}", history);
+    let diff: Vec<_> = history.iter().tuple_windows().map(|(a, b)| b.checked_sub(*a).ok_or_else(overflow)).try_collect()?;
     let diff = match diff.iter().all_equal_value() {
         Ok(&x) => x,
         Err(Some(..)) => predict(&diff)?,
         Err(None) => bail!("history should have at least 2 elements"),
     };
-    Ok(history[history.len() - 1] + diff)
+    history[history.len() - 1].checked_add(diff).ok_or_else(overflow)
+}
+
+/// `size` histories of 21 values of cubic polynomials, small enough for the predictions to add up without
+/// overflowing for up to about 100000 histories, and positive on both ends.
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for _ in 0..size {
+        let [b, c, d] = [rng.below(10), rng.below(5), rng.below(2)].map(|n| n as i64);
+        // At least `b + d`, so that the value before the first is not negative.
+        let a = b + d + rng.below(10) as i64;
+        let values = (0..21).map(|x: i64| a + b * x + c * x * x + d * x * x * x);
+        input.push_str(&format!("{}\n", values.format(" ")));
+    }
+    input
 }
 
-solution!(part1 => 1868368343, part2 => 1022);
+solution!(title: "Mirage Maintenance", parse: parse_histories, part1 => 1868368343, part2 => 1022, generate: generate);
 
 #[cfg(test)]
 mod tests {
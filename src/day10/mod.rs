@@ -1,143 +1,205 @@
 use crate::*;
 
-type Direction = (isize, isize);
-const L: Direction = (0, -1);
-const R: Direction = (0, 1);
-const U: Direction = (-1, 0);
-const D: Direction = (1, 0);
-const DIRS: &[Direction] = &[L, R, U, D];
-
-struct Maze<'a> {
-    grids: Vec<&'a [u8]>,
-    nrows: usize,
-    ncols: usize,
+use Direction::*;
+
+struct Maze {
+    grid:  Grid<u8>,
+    start: Point,
 }
 
-impl<'a> Maze<'a> {
-    fn get(&self, (row, col): (usize, usize)) -> Option<u8> {
-        (row < self.nrows && col < self.ncols).then(|| self.grids[row][col])
+/// Direction out of a pipe entered going `dir`, if it connects.
+fn pipe(dir: Direction, tile: u8) -> Option<Direction> {
+    match (dir, tile) {
+        (Left, b'F') | (Right, b'7') | (Down, b'|') => Some(Down),
+        (Right, b'J') | (Left, b'L') | (Up, b'|') => Some(Up),
+        (Up, b'F') | (Down, b'L') | (Right, b'-') => Some(Right),
+        (Up, b'7') | (Down, b'J') | (Left, b'-') => Some(Left),
+        _ => None,
     }
+}
 
-    fn loop_length(&self, start: (usize, usize)) -> Option<usize> {
-        DIRS.iter().find_map(|&dir| {
+impl Maze {
+    fn loop_length(&self, start: Point) -> Option<usize> {
+        Direction::ALL.into_iter().find_map(|dir| {
             let (mut dir, mut pos, mut steps) = (dir, start, 0);
             loop {
-                pos = forward(pos, dir);
+                pos = self.grid.step(pos, dir)?;
                 steps += 1;
                 if pos == start {
                     break;
                 }
-
-                dir = match (dir, self.get(pos)?) {
-                    (L, b'F') | (R, b'7') | (D, b'|') => D,
-                    (R, b'J') | (L, b'L') | (U, b'|') => U,
-                    (U, b'F') | (D, b'L') | (R, b'-') => R,
-                    (U, b'7') | (D, b'J') | (L, b'-') => L,
-                    _ => None?,
-                };
+                dir = pipe(dir, self.grid[pos])?;
             }
             Some(steps)
         })
     }
 
-    fn loop_enclosed(&self, start: (usize, usize)) -> Option<usize> {
-        DIRS.iter().find_map(|&dir| {
+    fn loop_enclosed(&self, start: Point) -> Option<usize> {
+        let (nrows, ncols) = (self.grid.nrows() * 2, self.grid.ncols() * 2);
+        Direction::ALL.into_iter().find_map(|dir| {
             let (mut dir, mut pos) = (dir, start);
-            let mut grids = vec![vec![b'I'; self.ncols * 2]; self.nrows * 2];
+            let mut doubled = Grid::filled(nrows, ncols, b'I');
 
             loop {
-                grids[pos.0 * 2][pos.1 * 2] = b'#';
-                let interpos = match dir {
-                    L => (pos.0 * 2, pos.1 * 2 - 1),
-                    R => (pos.0 * 2, pos.1 * 2 + 1),
-                    U => (pos.0 * 2 - 1, pos.1 * 2),
-                    D => (pos.0 * 2 + 1, pos.1 * 2),
-                    _ => unreachable!(),
-                };
-                grids[interpos.0][interpos.1] = b'#';
-
-                pos = forward(pos, dir);
+                // A path leaving the maze is not a loop.
+                let next = self.grid.step(pos, dir)?;
+                doubled[pos * 2] = b'#';
+                // Halfway between `pos` and `next` on the doubled grid.
+                doubled[pos + next] = b'#';
+
+                pos = next;
                 if pos == start {
                     break;
                 }
-
-                dir = match (dir, self.get(pos)?) {
-                    (L, b'F') | (R, b'7') | (D, b'|') => D,
-                    (R, b'J') | (L, b'L') | (U, b'|') => U,
-                    (U, b'F') | (D, b'L') | (R, b'-') => R,
-                    (U, b'7') | (D, b'J') | (L, b'-') => L,
-                    _ => None?,
-                };
+                dir = pipe(dir, self.grid[pos])?;
             }
 
-            (0..self.nrows * 2)
-                .cartesian_product([0, self.ncols * 2 - 1])
-                .chain([0, self.nrows * 2 - 1].into_iter().cartesian_product(0..self.ncols * 2))
-                .for_each(|pos| dfs(&mut grids, pos));
+            (0..nrows)
+                .cartesian_product([0, ncols - 1])
+                .chain([0, nrows - 1].into_iter().cartesian_product(0..ncols))
+                .for_each(|pos| dfs(&mut doubled, pos.into()));
 
-            grids
-                .into_iter()
+            doubled
+                .rows()
                 .step_by(2)
-                .flat_map(|row| row.into_iter().step_by(2))
-                .filter(|&x| x == b'I')
+                .flat_map(|row| row.iter().step_by(2))
+                .filter(|&&x| x == b'I')
                 .count()
                 .into()
         })
     }
 }
 
-fn dfs(grids: &mut [Vec<u8>], (row, col): (usize, usize)) {
-    if row >= grids.len() || col >= grids[row].len() || grids[row][col] != b'I' {
-        return;
-    }
+/// Clears the tiles marked `I` connected to `pos`. Iterative, as a recursion as deep as the area of a large maze
+/// overflows the stack.
+fn dfs(grid: &mut Grid<u8>, pos: Point) {
+    let mut stack = vec![pos];
+    while let Some(pos) = stack.pop() {
+        if grid.get(pos) != Some(&b'I') {
+            continue;
+        }
 
-    grids[row][col] = b' ';
-    DIRS.iter()
-        .map(|&dir| forward((row, col), dir))
-        .for_each(|(row, col)| dfs(grids, (row, col)));
+        grid[pos] = b' ';
+        stack.extend(grid.neighbours4(pos));
+    }
 }
 
-fn forward(pos: (usize, usize), dir: Direction) -> (usize, usize) {
-    (pos.0.wrapping_add_signed(dir.0), pos.1.wrapping_add_signed(dir.1))
+fn parse_maze(input: &str) -> Result<Maze> {
+    let grid = Grid::parse(input)?;
+    let start = grid.position(|&c| c == b'S').ok_or_else(|| anyhow!("no start found"))?;
+    Ok(Maze { grid, start })
 }
 
-fn part1(input: &str) -> Result<usize> {
-    let grids = input.lines().map(|line| line.as_bytes()).collect::<Vec<_>>();
-    let nrows = grids.len();
-    let ncols = match grids.iter().map(|line| line.len()).all_equal_value() {
-        Ok(ncols) => ncols,
-        _ => bail!("invalid input"),
-    };
-
-    let start = (0..nrows)
-        .cartesian_product(0..ncols)
-        .find(|&(r, c)| grids[r][c] == b'S')
-        .ok_or_else(|| anyhow!("no start found"))?;
-
-    let maze = Maze { grids, nrows, ncols };
+fn part1(maze: &Maze) -> Result<usize> {
+    maze.loop_length(maze.start).map(|n| n / 2).ok_or(anyhow!("no loop found"))
+}
 
-    maze.loop_length(start).map(|n| n / 2).ok_or(anyhow!("no loop found"))
+fn part2(maze: &Maze) -> Result<usize> {
+    maze.loop_enclosed(maze.start).ok_or(anyhow!("no loop found"))
 }
 
-fn part2(input: &str) -> Result<usize> {
-    let grids = input.lines().map(|line| line.as_bytes()).collect::<Vec<_>>();
-    let nrows = grids.len();
-    let ncols = match grids.iter().map(|line| line.len()).all_equal_value() {
-        Ok(ncols) => ncols,
-        _ => bail!("invalid input"),
-    };
+/// A square maze of about `size` tiles. Its loop goes around a random tree of 3x3 blocks of tiles: each block is
+/// a ring of pipes, and the rings of joined blocks are opened and linked to each other. The loop encloses the
+/// centres of the blocks and the tiles between joined blocks, and the tiles off the loop are random pipes.
+fn generate(size: usize, rng: &mut Rng) -> String {
+    const UP: u8 = 1;
+    const DOWN: u8 = 2;
+    const LEFT: u8 = 4;
+    const RIGHT: u8 = 8;
+    // Links or unlinks the tile at `(row, col)` and its neighbour below or on its right.
+    fn link(links: &mut Grid<u8>, (row, col): (usize, usize), down: bool, on: bool) {
+        let pos = Point::new(row, col);
+        let (next, here, there) = match down {
+            true => (pos + Point::new(1, 0), DOWN, UP),
+            false => (pos + Point::new(0, 1), RIGHT, LEFT),
+        };
+        match on {
+            true => (links[pos], links[next]) = (links[pos] | here, links[next] | there),
+            false => (links[pos], links[next]) = (links[pos] & !here, links[next] & !there),
+        }
+    }
+
+    let blocks = (size / 9).isqrt().max(1);
+    let side = blocks * 3;
 
-    let start = (0..nrows)
-        .cartesian_product(0..ncols)
-        .find(|&(i, j)| grids[i][j] == b'S')
-        .ok_or_else(|| anyhow!("no start found"))?;
+    // A random spanning tree of about half of the blocks, grown from a random one.
+    let mut in_tree = Grid::filled(blocks, blocks, false);
+    let (mut tree, mut edges, mut frontier) = (vec![], vec![], vec![]);
+    let mut block = Point::new(rng.below(blocks), rng.below(blocks));
+    loop {
+        in_tree[block] = true;
+        tree.push(block);
+        frontier.extend(in_tree.neighbours4(block).map(|next| (block, next)));
+        frontier.retain(|&(_, next)| !in_tree[next]);
+        if tree.len() >= (blocks * blocks).div_ceil(2) || frontier.is_empty() {
+            break;
+        }
+        let (from, to) = frontier.swap_remove(rng.below(frontier.len()));
+        // Upper or left block first.
+        edges.push((min(from, to), max(from, to)));
+        block = to;
+    }
 
-    let maze = Maze { grids, nrows, ncols };
+    let mut links = Grid::filled(side, side, 0);
+    for block in &tree {
+        let (r, c) = (block.row * 3, block.col * 3);
+        for (pos, down) in [
+            ((r, c), false),
+            ((r, c + 1), false),
+            ((r + 2, c), false),
+            ((r + 2, c + 1), false),
+            ((r, c), true),
+            ((r + 1, c), true),
+            ((r, c + 2), true),
+            ((r + 1, c + 2), true),
+        ] {
+            link(&mut links, pos, down, true);
+        }
+    }
+    for (from, to) in edges {
+        let same_row = from.row == to.row;
+        let (r, c) = (from.row * 3, from.col * 3);
+        match same_row {
+            // Opens the right side of the left block and the left side of the right one, and links their corners.
+            true => {
+                for pos in [(r, c + 2), (r + 1, c + 2), (r, c + 3), (r + 1, c + 3)] {
+                    link(&mut links, pos, true, false);
+                }
+                link(&mut links, (r, c + 2), false, true);
+                link(&mut links, (r + 2, c + 2), false, true);
+            }
+            // Same with the bottom side of the upper block and the top side of the lower one.
+            false => {
+                for pos in [(r + 2, c), (r + 2, c + 1), (r + 3, c), (r + 3, c + 1)] {
+                    link(&mut links, pos, false, false);
+                }
+                link(&mut links, (r + 2, c), true, true);
+                link(&mut links, (r + 2, c + 2), true, true);
+            }
+        }
+    }
 
-    maze.loop_enclosed(start).ok_or(anyhow!("no loop found"))
+    let mut tiles = links.map(|&links| match links {
+        l if l == UP | DOWN => b'|',
+        l if l == LEFT | RIGHT => b'-',
+        l if l == UP | RIGHT => b'L',
+        l if l == UP | LEFT => b'J',
+        l if l == DOWN | LEFT => b'7',
+        l if l == DOWN | RIGHT => b'F',
+        _ => rng.pick(b"|-LJ7F."),
+    });
+    // The start is anywhere on the loop, with no pipes around it other than those of the loop.
+    let on_loop = links.positions().filter(|&pos| links[pos] != 0).collect_vec();
+    let start = rng.pick(&on_loop);
+    tiles[start] = b'S';
+    for pos in links.neighbours4(start).filter(|&pos| links[pos] == 0) {
+        tiles[pos] = b'.';
+    }
+    let input = tiles.rows().map(String::from_utf8_lossy).join("\n");
+    input + "\n"
 }
 
-solution!(part1 => 6778, part2 => 433);
+solution!(title: "Pipe Maze", parse: parse_maze, part1 => 6778, part2 => 433, generate: generate);
 
 #[cfg(test)]
 mod tests {
@@ -199,7 +261,7 @@ mod tests {
         L7JLJL-JLJLJL--JLJ.L
     "};
 
-    crate::test!(part1, t1: EXAMPLE1 => 4);
+    crate::test!(part1, t1: EXAMPLE1 => 4, edge: "S7\nLJ" => 2);
 
     crate::test!(part2,
         t1: EXAMPLE1 => 1,
@@ -207,5 +269,6 @@ mod tests {
         t3: EXAMPLE3 => 4,
         t4: EXAMPLE4 => 8,
         t5: EXAMPLE5 => 10,
+        edge: "S7\nLJ" => 0,
     );
 }
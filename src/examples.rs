@@ -0,0 +1,80 @@
+use crate::*;
+use anyhow::Context;
+use std::{fs, io::ErrorKind, path::Path};
+
+/// An example input from the `examples` directory of a day: `<name>.txt`, with the expected answers in the
+/// optional sidecar files `<name>.part1` and `<name>.part2`.
+pub struct Example {
+    pub name:     String,
+    pub text:     String,
+    pub expected: [Option<Answer>; 2],
+}
+
+/// Loads the examples in `dir`, ordered by name. A missing directory holds no examples.
+pub fn load_examples(dir: &Path) -> Result<Vec<Example>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", dir.display())),
+    };
+    let read = |path: &Path| fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()));
+
+    let mut examples = vec![];
+    for entry in entries {
+        let path = entry
+            .with_context(|| format!("failed to read {}", dir.display()))?
+            .path();
+        if path.extension().is_none_or(|ext| ext != "txt") {
+            continue;
+        }
+        let name = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        let mut expected = [None, None];
+        for (part, answer) in (1..).zip(&mut expected) {
+            let sidecar = path.with_extension(format!("part{part}"));
+            if sidecar.exists() {
+                *answer = read(&sidecar)?.parse().ok();
+            }
+        }
+        examples.push(Example { name, text: read(&path)?, expected });
+    }
+    examples.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    Ok(examples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A temporary directory, removed when dropped, even if the test fails.
+    struct TempDir(std::path::PathBuf);
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn load() -> Result<()> {
+        let temp = TempDir(std::env::temp_dir().join(format!("aoc2023-examples-{}", std::process::id())));
+        let dir = &temp.0;
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join("b.txt"), "1 2\n")?;
+        fs::write(dir.join("b.part2"), "-3\n")?;
+        fs::write(dir.join("a.txt"), "4\n")?;
+        fs::write(dir.join("a.part1"), "4\n")?;
+        fs::write(dir.join("notes.md"), "not an example")?;
+
+        let examples = load_examples(dir)?;
+        let summary = examples
+            .iter()
+            .map(|e| (e.name.as_str(), e.text.as_str(), e.expected.clone()))
+            .collect_vec();
+        assert_eq!(summary, [
+            ("a", "4\n", [Some(4.into_answer()), None]),
+            ("b", "1 2\n", [None, Some((-3).into_answer())]),
+        ]);
+        assert!(load_examples(&dir.join("missing"))?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+use anyhow::Context;
+use aoc2023::{bail, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Source of puzzle inputs.
+pub trait Fetcher {
+    fn fetch(&self, day: u8) -> Result<String>;
+}
+
+impl<F: Fetcher + ?Sized> Fetcher for Box<F> {
+    fn fetch(&self, day: u8) -> Result<String> {
+        (**self).fetch(day)
+    }
+}
+
+/// Downloads inputs from the Advent of Code website, or anything serving the same paths.
+#[cfg(feature = "fetch")]
+pub struct Http {
+    /// URL the `/2023/day/N/input` paths are relative to, e.g. `https://adventofcode.com`.
+    pub base_url: String,
+    /// Value of the `session` cookie of a logged-in account.
+    pub session:  String,
+}
+
+#[cfg(feature = "fetch")]
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+#[cfg(feature = "fetch")]
+impl Fetcher for Http {
+    fn fetch(&self, day: u8) -> Result<String> {
+        let url = format!("{}/2023/day/{day}/input", self.base_url.trim_end_matches('/'));
+        let response = ureq::get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set(
+                "User-Agent",
+                concat!(env!("CARGO_PKG_NAME"), " by ", env!("CARGO_PKG_AUTHORS")),
+            )
+            .call();
+        match response {
+            Ok(response) => response
+                .into_string()
+                .with_context(|| format!("failed to read the input of day {day} from {url}")),
+            Err(ureq::Error::Status(404, _)) => bail!("the input of day {day} is not available yet ({url})"),
+            Err(ureq::Error::Status(400, _)) => bail!("{url} rejected the session token"),
+            Err(e) => Err(e).with_context(|| format!("failed to fetch the input of day {day}")),
+        }
+    }
+}
+
+/// Reads inputs saved as `dayNN.txt` in a directory.
+pub struct Local {
+    pub dir: PathBuf,
+}
+
+impl Fetcher for Local {
+    fn fetch(&self, day: u8) -> Result<String> {
+        let path = file_name(&self.dir, day);
+        fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))
+    }
+}
+
+/// Keeps a copy of everything `inner` fetches in `dir`, so that nothing is fetched twice.
+#[cfg(feature = "fetch")]
+pub struct Cached<F> {
+    pub inner: F,
+    pub dir:   PathBuf,
+}
+
+#[cfg(feature = "fetch")]
+impl<F: Fetcher> Fetcher for Cached<F> {
+    fn fetch(&self, day: u8) -> Result<String> {
+        let path = file_name(&self.dir, day);
+        if let Ok(input) = fs::read_to_string(&path) {
+            return Ok(input);
+        }
+        let input = self.inner.fetch(day)?;
+        // Written under another name first, so that an interrupted write is never taken for a cached input.
+        let partial = path.with_extension("part");
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(&partial, &input))
+            .and_then(|_| fs::rename(&partial, &path))
+            .with_context(|| format!("failed to cache the input of day {day} in {}", path.display()))?;
+        Ok(input)
+    }
+}
+
+fn file_name(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{day:02}.txt"))
+}
+
+/// Directory in `cache` for the inputs downloaded from `base_url` with `session`: inputs differ from one account to
+/// the other, so each server and session gets its own, named after the server and a hash of the session.
+#[cfg(feature = "fetch")]
+fn cache_dir(cache: &Path, base_url: &str, session: &str) -> PathBuf {
+    let server = base_url.split_once("://").map_or(base_url, |(_, rest)| rest);
+    let server: String = server
+        .trim_end_matches('/')
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() || c == '.' {
+            true => c,
+            false => '_',
+        })
+        .collect();
+    // FNV-1a, which unlike the hashers of std is stable from one release to the next.
+    let hash = session.bytes().fold(0xcbf29ce484222325u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x100000001b3)
+    });
+    cache.join(format!("{server}-{hash:016x}"))
+}
+
+/// Builds the fetcher configured by the environment:
+///
+/// - `AOC_INPUT_DIR`: read inputs from this directory instead of downloading them;
+/// - `AOC_SESSION`: session token used to download inputs;
+/// - `AOC_BASE_URL`: where to download inputs from, `https://adventofcode.com` by default;
+/// - `AOC_CACHE_DIR`: where downloaded inputs are kept, `~/.cache/aoc2023` by default.
+///
+/// Downloading takes the `fetch` feature.
+pub fn from_env() -> Result<Box<dyn Fetcher>> {
+    match var("AOC_INPUT_DIR") {
+        Some(dir) => Ok(Box::new(Local { dir: dir.into() })),
+        None => download(),
+    }
+}
+
+/// Value of the environment variable `name`, if it is set and not empty.
+fn var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+#[cfg(not(feature = "fetch"))]
+fn download() -> Result<Box<dyn Fetcher>> {
+    bail!("downloading inputs needs the fetch feature; set AOC_INPUT_DIR to read them from a directory instead")
+}
+
+/// Downloads inputs over HTTP into a cache, as configured by the variables read by [`from_env`].
+#[cfg(feature = "fetch")]
+fn download() -> Result<Box<dyn Fetcher>> {
+    let Some(session) = var("AOC_SESSION") else {
+        bail!("set AOC_SESSION to the session cookie of your account to download inputs, or AOC_INPUT_DIR to read them from a directory")
+    };
+    let dir = match var("AOC_CACHE_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => match var("XDG_CACHE_HOME").or_else(|| var("HOME").map(|home| format!("{home}/.cache"))) {
+            Some(cache) => Path::new(&cache).join(env!("CARGO_PKG_NAME")),
+            None => bail!("cannot find a cache directory for inputs; set AOC_CACHE_DIR"),
+        },
+    };
+    let base_url = var("AOC_BASE_URL").unwrap_or_else(|| BASE_URL.to_owned());
+    let dir = cache_dir(&dir, &base_url, &session);
+    Ok(Box::new(Cached { inner: Http { base_url, session }, dir }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "fetch")]
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    /// Stand-in for the website: answers each request with the day from its path and reports the request line and
+    /// cookie of each.
+    #[cfg(feature = "fetch")]
+    fn serve(requests: usize) -> Result<(String, mpsc::Receiver<(String, String)>)> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming().take(requests).flatten() {
+                let lines: Vec<_> = BufReader::new(&stream)
+                    .lines()
+                    .map_while(|line| line.ok())
+                    .take_while(|line| !line.is_empty())
+                    .collect();
+                let request = lines.first().cloned().unwrap_or_default();
+                let cookie = lines
+                    .iter()
+                    .find_map(|line| line.strip_prefix("Cookie: "))
+                    .unwrap_or_default();
+                let response = match request.split('/').nth(3) {
+                    Some("25") => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_owned(),
+                    Some(day) => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\ninput {day}\n",
+                        day.len() + 7
+                    ),
+                    None => "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n".to_owned(),
+                };
+                let _ = (&stream).write_all(response.as_bytes());
+                let _ = tx.send((request, cookie.to_owned()));
+            }
+        });
+        Ok((base_url, rx))
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("aoc2023-{name}-{}", std::process::id()))
+    }
+
+    #[test]
+    fn local() -> Result<()> {
+        let dir = temp_dir("local");
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("day03.txt"), "input 3\n")?;
+        assert_eq!(Local { dir: dir.clone() }.fetch(3)?, "input 3\n");
+        assert!(Local { dir: dir.clone() }.fetch(5).is_err());
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn http() -> Result<()> {
+        let (base_url, requests) = serve(2)?;
+        let http = Http { base_url: format!("{base_url}/"), session: "secret".into() };
+        assert_eq!(http.fetch(7)?, "input 7\n");
+        assert_eq!(
+            requests.recv()?,
+            ("GET /2023/day/7/input HTTP/1.1".into(), "session=secret".into())
+        );
+        assert!(http.fetch(25).unwrap_err().to_string().contains("not available yet"));
+        Ok(())
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn cached() -> Result<()> {
+        let (base_url, requests) = serve(2)?;
+        let dir = temp_dir("cache");
+        let cached = Cached {
+            inner: Http { base_url, session: "secret".into() },
+            dir:   dir.clone(),
+        };
+        assert_eq!(cached.fetch(3)?, "input 3\n");
+        assert_eq!(cached.fetch(3)?, "input 3\n");
+        assert_eq!(cached.fetch(4)?, "input 4\n");
+        assert_eq!(requests.iter().count(), 2);
+        assert_eq!(Local { dir: dir.clone() }.fetch(3)?, "input 3\n");
+        assert!(Local { dir: dir.clone() }.fetch(5).is_err());
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn cache_dirs() {
+        let cache = Path::new("cache");
+        let dir = cache_dir(cache, "https://adventofcode.com/", "secret");
+        assert!(dir.starts_with(cache));
+        let name = dir.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        assert!(name.starts_with("adventofcode.com-") && !name.contains("secret"));
+        assert_eq!(dir, cache_dir(cache, "https://adventofcode.com", "secret"));
+        assert_ne!(dir, cache_dir(cache, "https://adventofcode.com", "other"));
+        assert_ne!(dir, cache_dir(cache, "http://127.0.0.1:8080", "secret"));
+        assert_eq!(
+            cache_dir(cache, "http://127.0.0.1:8080/aoc", "").file_name(),
+            Some("127.0.0.1_8080_aoc-cbf29ce484222325".as_ref())
+        );
+    }
+}
@@ -0,0 +1,242 @@
+//! Feeds truncated, mutated and random inputs to every part of every registered solution, and checks that none
+//! of them panics: malformed input must be reported as an error.
+use crate::*;
+use std::{
+    any::Any,
+    cell::RefCell,
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+    thread,
+};
+
+/// Variants of each kind generated per day.
+const CASES: usize = 64;
+/// Prefix of the names of the threads running the harness, whose panics are expected and not printed.
+const THREAD: &str = "fuzz ";
+
+#[derive(Clone, Copy, Debug)]
+enum Kind {
+    Truncated,
+    Mutated,
+    Random,
+}
+
+/// Malformed variants of `input`, made of its own characters and a few others likely to trip parsers.
+fn variants(input: &str, rng: &mut Rng) -> Vec<(Kind, String)> {
+    let chars = input.chars().collect_vec();
+    let mut alphabet = chars.iter().copied().unique().collect_vec();
+    alphabet.extend(" \n:-+,0123456789".chars());
+
+    let mut variants = Vec::with_capacity(3 * CASES);
+    for _ in 0..CASES {
+        variants.push((Kind::Truncated, chars[..rng.below(chars.len() + 1)].iter().collect()));
+        variants.push((Kind::Mutated, mutate(&chars, &alphabet, rng)));
+        let len = rng.below(256);
+        variants.push((Kind::Random, (0..len).map(|_| rng.pick(&alphabet)).collect()));
+    }
+    variants
+}
+
+/// Applies a few random edits to `chars`: replacing, inserting or removing characters, overwriting them with a run of
+/// digits too long for any integer, or removing or duplicating lines.
+fn mutate(chars: &[char], alphabet: &[char], rng: &mut Rng) -> String {
+    let mut chars = chars.to_vec();
+    for _ in 0..=rng.below(3) {
+        let at = rng.below(chars.len());
+        let line = |chars: &[char]| {
+            let start = chars[..at].iter().rposition(|&c| c == '\n').map_or(0, |i| i + 1);
+            let end = chars[at..]
+                .iter()
+                .position(|&c| c == '\n')
+                .map_or(chars.len(), |i| at + i + 1);
+            start..end
+        };
+        match rng.below(6) {
+            0 if at < chars.len() => chars[at] = rng.pick(alphabet),
+            1 => chars.insert(at, rng.pick(alphabet)),
+            2 if at < chars.len() => drop(chars.remove(at)),
+            3 => drop(chars.drain(line(&chars))),
+            // Overwritten rather than inserted, so that the lines of a grid keep the same length.
+            4 => chars[at..]
+                .iter_mut()
+                .take(40)
+                .take_while(|c| **c != '\n')
+                .for_each(|c| *c = '1'),
+            _ => {
+                let line = line(&chars);
+                let copy = chars[line.clone()].to_vec();
+                chars.splice(line.end..line.end, copy);
+            }
+        }
+    }
+    chars.into_iter().collect()
+}
+
+thread_local! {
+    /// Where the last panic of a harness thread happened, recorded by the panic hook.
+    static LOCATION: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// A panic: where it happened, and its message.
+#[derive(Clone, PartialEq, Eq)]
+struct Panic {
+    location: String,
+    message:  String,
+}
+
+/// Runs `part` on `input`, returning the panic if it panics.
+fn panics(solution: &Solution, part: u8, input: &str) -> Option<Panic> {
+    let payload = panic::catch_unwind(AssertUnwindSafe(|| solution.solve(part, input))).err()?;
+    let location = LOCATION.with(|location| location.take());
+    Some(Panic { location, message: message(payload) })
+}
+
+fn message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(s) => *s,
+        Err(payload) => payload.downcast_ref::<&str>().map_or("(no message)", |s| s).to_owned(),
+    }
+}
+
+/// Shrinks `input` to a smaller one that still makes `fails` true: whole lines are removed first, then characters,
+/// in chunks of decreasing size.
+fn minimise(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let lines = input.split_inclusive('\n').map(str::to_owned).collect_vec();
+    let lines = shrink(lines, &fails);
+    let chars = lines.concat().chars().map(String::from).collect_vec();
+    shrink(chars, &fails).concat()
+}
+
+fn shrink(mut items: Vec<String>, fails: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut chunk = items.len().div_ceil(2);
+    while chunk > 0 {
+        let mut start = 0;
+        while start < items.len() {
+            let end = min(start + chunk, items.len());
+            let candidate = [&items[..start], &items[end..]].concat();
+            match fails(&candidate.concat()) {
+                true => items = candidate,
+                false => start += chunk,
+            }
+        }
+        chunk /= 2;
+    }
+    items
+}
+
+/// A part panicking on a malformed input.
+struct Failure {
+    day:     String,
+    part:    u8,
+    kind:    Kind,
+    panic:   Panic,
+    /// Smallest input found to panic at the same location.
+    minimal: String,
+}
+
+fn fuzz(day: &str, solution: &Solution) -> Vec<Failure> {
+    let seed = day.bytes().fold(0, |acc, b| acc * 31 + u64::from(b));
+    let mut failures: Vec<Failure> = vec![];
+    for (kind, input) in variants(solution.input(), &mut Rng::new(seed)) {
+        for part in [1, 2] {
+            // One reproducer per part and location is enough.
+            let Some(panic) = panics(solution, part, &input) else {
+                continue;
+            };
+            if failures
+                .iter()
+                .any(|f| f.part == part && f.panic.location == panic.location)
+            {
+                continue;
+            }
+            let same = |input: &str| panics(solution, part, input).is_some_and(|p| p.location == panic.location);
+            let minimal = minimise(&input, same);
+            failures.push(Failure { day: day.to_owned(), part, kind, panic, minimal });
+        }
+    }
+    failures
+}
+
+/// Records where the panics of the harness threads happen instead of printing them, and keeps the default output
+/// for every other thread.
+fn hook_panics() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            match thread::current().name().is_some_and(|name| name.starts_with(THREAD)) {
+                true => LOCATION.set(info.location().map_or_else(String::new, |l| l.to_string())),
+                false => default(info),
+            }
+        }));
+    });
+}
+
+#[test]
+fn malformed_inputs() {
+    hook_panics();
+    let failures = thread::scope(|scope| {
+        let handles = build_solutions()
+            .into_iter()
+            .map(|(day, solution)| {
+                thread::Builder::new()
+                    .name(format!("{THREAD}{day}"))
+                    .spawn_scoped(scope, move || fuzz(&day, &solution))
+            })
+            .collect_vec();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                // A harness thread only panics outside of the solutions, which is a bug of the harness itself.
+                let handle = handle.expect("failed to spawn a fuzzing thread");
+                handle.join().unwrap_or_else(|payload| panic::resume_unwind(payload))
+            })
+            .collect_vec()
+    });
+
+    let report = failures
+        .iter()
+        .map(|Failure { day, part, kind, panic, minimal }| {
+            let Panic { location, message } = panic;
+            format!(
+                "{day} part {part} panics at {location} on a {kind:?} input: {message}\n    reproducer: {minimal:?}"
+            )
+        })
+        .join("\n");
+    assert!(failures.is_empty(), "{} panic(s) found:\n{report}", failures.len());
+}
+
+#[test]
+fn known_panics() {
+    let solutions = build_solutions();
+    let cases = [
+        // Part numbers and gear ratios overflowing.
+        ("day03", 1, "11111111111111111111111*\n"),
+        ("day03", 2, "11111111111111111111111*\n...........1...........\n"),
+        ("day03", 2, "1111111111*\n..........1111111111\n"),
+        // Wins copies of cards past the end of the table.
+        ("day04", 2, "Card 1: 1 | 1\n"),
+        ("day07", 1, "AAAAAA 1\n"),
+        // Missing node, and `ZZZ` unreachable.
+        ("day08", 1, "R\n\nAAA = (BBB, BBB)\n"),
+        ("day08", 1, "L\n\nAAA = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)\n"),
+        ("day08", 2, "L\n\nAAA = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)\n"),
+    ];
+    // Days compiled out by their feature are skipped.
+    for (day, part, input) in cases {
+        let Some(solution) = solutions.get(day) else {
+            continue;
+        };
+        assert!(
+            solution.solve(part, input).is_err(),
+            "{day} part {part} accepts {input:?}"
+        );
+    }
+}
+
+#[test]
+fn minimisation() {
+    let fails = |s: &str| s.contains('x') && s.contains('z');
+    assert_eq!(minimise("abc\ndef\nxyz\nghi\n", fails), "xz");
+    assert_eq!(minimise("abc", |s| s.is_empty()), "");
+}
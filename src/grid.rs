@@ -0,0 +1,300 @@
+use crate::*;
+use std::ops::{Index, IndexMut};
+
+/// Offsets of the 8 neighbours of a cell, diagonals included, row by row.
+#[rustfmt::skip]
+const ADJ8: [(isize, isize); 8] = [
+    (-1, -1), (-1, 0), (-1, 1),
+    ( 0, -1),          ( 0, 1),
+    ( 1, -1), ( 1, 0), ( 1, 1),
+];
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    nrows: usize,
+    ncols: usize,
+}
+
+impl Grid<u8> {
+    /// Parses the bytes of each line as a row. All rows must have the same length.
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::parse_with(input, Ok)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parses each line as a row, and each of its bytes as a cell with `f`. All rows must have the same length.
+    pub fn parse_with(input: &str, mut f: impl FnMut(u8) -> Result<T>) -> Result<Self> {
+        let (mut cells, mut nrows, mut ncols) = (Vec::with_capacity(input.len()), 0, 0);
+        for (i, line) in input.lines().enumerate() {
+            match i {
+                0 => ncols = line.len(),
+                _ => ensure!(
+                    line.len() == ncols,
+                    "row {} has {} cells, expected {}",
+                    i + 1,
+                    line.len(),
+                    ncols
+                ),
+            }
+            for c in line.bytes() {
+                cells.push(f(c)?);
+            }
+            nrows += 1;
+        }
+        Ok(Self { cells, nrows, ncols })
+    }
+
+    /// A grid of `nrows` rows of `ncols` cells, from its cells row by row.
+    pub fn new(nrows: usize, ncols: usize, cells: Vec<T>) -> Result<Self> {
+        ensure!(
+            nrows.checked_mul(ncols) == Some(cells.len()),
+            "{} cells cannot make {} rows of {}",
+            cells.len(),
+            nrows,
+            ncols
+        );
+        Ok(Self { cells, nrows, ncols })
+    }
+
+    /// A grid of `nrows` rows of `ncols` copies of `value`.
+    pub fn filled(nrows: usize, ncols: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self { cells: vec![value; nrows * ncols], nrows, ncols }
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    pub fn contains(&self, pos: Point) -> bool {
+        pos.row < self.nrows && pos.col < self.ncols
+    }
+
+    pub fn get(&self, pos: Point) -> Option<&T> {
+        self.contains(pos).then(|| &self.cells[pos.row * self.ncols + pos.col])
+    }
+
+    pub fn get_mut(&mut self, pos: Point) -> Option<&mut T> {
+        match self.contains(pos) {
+            true => Some(&mut self.cells[pos.row * self.ncols + pos.col]),
+            false => None,
+        }
+    }
+
+    /// The next position in direction `dir`, if it is inside the grid.
+    pub fn step(&self, pos: Point, dir: Direction) -> Option<Point> {
+        pos.step_within(dir, self.nrows, self.ncols)
+    }
+
+    /// Positions of all cells, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Point> {
+        (0..self.nrows).cartesian_product(0..self.ncols).map(Point::from)
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// Position of the first cell, row by row, for which `pred` holds.
+    pub fn position(&self, mut pred: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter().find(|(_, cell)| pred(cell)).map(|(pos, _)| pos)
+    }
+
+    /// First cell, row by row, for which `pred` holds, with its position.
+    pub fn find(&self, mut pred: impl FnMut(&T) -> bool) -> Option<(Point, &T)> {
+        self.iter().find(|(_, cell)| pred(cell))
+    }
+
+    /// Positions of the orthogonal neighbours of `pos` inside the grid, clockwise from the one above.
+    pub fn neighbours4(&self, pos: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL.into_iter().filter_map(move |dir| self.step(pos, dir))
+    }
+
+    /// Positions of the neighbours of `pos` inside the grid, diagonals included, row by row.
+    pub fn neighbours8(&self, pos: Point) -> impl Iterator<Item = Point> + '_ {
+        ADJ8.iter()
+            .filter_map(move |&offset| pos.offset(offset))
+            .filter(|&pos| self.contains(pos))
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.nrows).then(|| &self.cells[row * self.ncols..(row + 1) * self.ncols])
+    }
+
+    /// Rows from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.nrows).filter_map(|row| self.row(row))
+    }
+
+    /// Cells of column `col` from top to bottom, or nothing if there is no such column.
+    pub fn column(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> {
+        let rows = if col < self.ncols { self.nrows } else { 0 };
+        self.cells.iter().skip(col).step_by(self.ncols.max(1)).take(rows)
+    }
+
+    /// Columns from left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.ncols).map(|col| self.column(col))
+    }
+
+    /// Grid of the results of `f` on each cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            nrows: self.nrows,
+            ncols: self.ncols,
+        }
+    }
+
+    /// Grid whose rows are the columns of this one.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cells = self.columns().flatten().cloned().collect();
+        Self { cells, nrows: self.ncols, ncols: self.nrows }
+    }
+
+    /// Grid rotated a quarter turn clockwise: the first column, bottom to top, becomes the first row.
+    pub fn rotate_right(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..self.ncols)
+            .flat_map(|col| self.column(col).rev())
+            .cloned()
+            .collect();
+        Self { cells, nrows: self.ncols, ncols: self.nrows }
+    }
+
+    /// Grid rotated a quarter turn counter-clockwise: the last column, top to bottom, becomes the first row.
+    pub fn rotate_left(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..self.ncols)
+            .rev()
+            .flat_map(|col| self.column(col))
+            .cloned()
+            .collect();
+        Self { cells, nrows: self.ncols, ncols: self.nrows }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    /// The cell at `pos`. Panics if it is outside the grid, see [`Grid::get`].
+    fn index(&self, pos: Point) -> &T {
+        match self.get(pos) {
+            Some(cell) => cell,
+            None => panic!("{pos:?} is outside of a {}x{} grid", self.nrows, self.ncols),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, pos: Point) -> &mut T {
+        let (nrows, ncols) = (self.nrows, self.ncols);
+        match self.get_mut(pos) {
+            Some(cell) => cell,
+            None => panic!("{pos:?} is outside of a {nrows}x{ncols} grid"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID: &str = "abc\ndef\n";
+
+    fn p(row: usize, col: usize) -> Point {
+        Point::new(row, col)
+    }
+
+    #[test]
+    fn parse() -> Result<()> {
+        let grid = Grid::parse(GRID)?;
+        assert_eq!((grid.nrows(), grid.ncols()), (2, 3));
+        assert_eq!(grid.get(p(1, 2)), Some(&b'f'));
+        assert_eq!(grid.get(p(2, 0)), None);
+        assert_eq!(grid.get(p(0, 3)), None);
+        assert_eq!(
+            Grid::parse("abc\nde\n").err().map(|e| e.to_string()).as_deref(),
+            Some("row 2 has 2 cells, expected 3")
+        );
+        assert_eq!(Grid::parse("")?.nrows(), 0);
+
+        let digit = |c: u8| match c {
+            b'0'..=b'9' => Ok(c - b'0'),
+            _ => bail!("not a digit: {}", c as char),
+        };
+        assert_eq!(Grid::parse_with("12\n34\n", digit)?, Grid::new(2, 2, vec![1, 2, 3, 4])?);
+        assert!(Grid::parse_with("1x\n", digit).is_err());
+        assert!(Grid::new(2, 2, vec![1, 2, 3]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn access() -> Result<()> {
+        let mut grid = Grid::parse(GRID)?;
+        *grid.get_mut(p(0, 1)).ok_or_else(|| anyhow!("out of bounds"))? = b'x';
+        assert!(grid.get_mut(p(5, 5)).is_none());
+        grid[p(1, 2)] = b'y';
+        assert_eq!(grid[p(1, 2)], b'y');
+        assert_eq!(grid.position(|&c| c == b'x'), Some(p(0, 1)));
+        assert_eq!(grid.find(|c| (b'e'..b'x').contains(c)), Some((p(1, 1), &b'e')));
+        assert_eq!(grid.position(|&c| c == b'z'), None);
+        assert_eq!(grid.iter().nth(3), Some((p(1, 0), &b'd')));
+        Ok(())
+    }
+
+    #[test]
+    fn neighbours() -> Result<()> {
+        let grid = Grid::filled(3, 3, 0);
+        assert_eq!(grid.neighbours4(p(0, 0)).collect_vec(), [p(0, 1), p(1, 0)]);
+        assert_eq!(grid.neighbours4(p(1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8(p(0, 2)).collect_vec(), [p(0, 1), p(1, 1), p(1, 2)]);
+        assert_eq!(grid.neighbours8(p(1, 1)).count(), 8);
+        assert_eq!(grid.neighbours8(p(2, 2)).collect_vec(), [p(1, 1), p(1, 2), p(2, 1)]);
+        assert_eq!(grid.step(p(2, 1), Direction::Down), None);
+        assert_eq!(grid.step(p(2, 1), Direction::Up), Some(p(1, 1)));
+        Ok(())
+    }
+
+    #[test]
+    fn rows_and_columns() -> Result<()> {
+        let grid = Grid::parse(GRID)?;
+        assert_eq!(grid.rows().collect_vec(), [b"abc", b"def"]);
+        assert_eq!(grid.row(1), Some(&b"def"[..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(grid.column(1).copied().collect_vec(), b"be");
+        assert_eq!(grid.column(3).count(), 0);
+        assert_eq!(grid.columns().map(|col| col.copied().collect_vec()).collect_vec(), [
+            b"ad", b"be", b"cf"
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn transform() -> Result<()> {
+        let grid = Grid::parse(GRID)?;
+        assert_eq!(grid.transpose(), Grid::parse("ad\nbe\ncf\n")?);
+        assert_eq!(grid.rotate_right(), Grid::parse("da\neb\nfc\n")?);
+        assert_eq!(grid.rotate_left(), Grid::parse("cf\nbe\nad\n")?);
+        assert_eq!(grid.rotate_right().rotate_right().rotate_right(), grid.rotate_left());
+        assert_eq!(grid.map(|c| c.to_ascii_uppercase()), Grid::parse("ABC\nDEF\n")?);
+        Ok(())
+    }
+}
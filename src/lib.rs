@@ -1,30 +1,27 @@
-#![feature(iter_intersperse)]
-#![feature(iter_array_chunks)]
-#![feature(iterator_try_collect)]
-#![feature(array_windows)]
-#![feature(extract_if)]
-#![feature(test)]
+#![cfg_attr(feature = "nightly", feature(test))]
+#![forbid(unsafe_code)]
 #![allow(clippy::get_first)]
 // #![deny(clippy::pedantic)]
+#[cfg(feature = "nightly")]
 extern crate test;
 
+mod answer;
+mod examples;
+#[cfg(test)]
+mod fuzz;
+mod grid;
 mod macros;
+pub mod math;
+pub mod parse;
+mod point;
+pub mod ranges;
+mod rng;
+mod solution;
 #[cfg(test)]
 mod testmacros;
 
-#[rustfmt::skip]
-crate::aoc!(
-    day01,
-    day02,
-    day03,
-    day04,
-    day05,
-    day06,
-    day07,
-    day08,
-    day09,
-    day10,
-); // +SOLUTIONS+
+// The modules of the days and `build_solutions`, see `build.rs`.
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
 
 pub use std::{
     cmp::{max, min, Ordering, Reverse},
@@ -33,11 +30,25 @@ pub use std::{
     str::FromStr,
 };
 
+pub use answer::{Answer, Expected, IntoAnswer};
 pub use anyhow::{anyhow, bail, ensure, Error, Result};
+pub use examples::{load_examples, Example};
+pub use grid::Grid;
 pub use itertools::Itertools;
-
-pub struct Solution {
-    pub part1: fn(&str) -> Result<String>,
-    pub part2: fn(&str) -> Result<String>,
-    pub input: &'static str,
-}
+pub use point::{Direction, Point};
+pub use ranges::{PiecewiseMap, RangeSet};
+pub use rng::Rng;
+pub use solution::{
+    day_of_module,
+    expected_part1,
+    expected_part2,
+    with_named_inputs,
+    Day,
+    DynDay,
+    FnDay,
+    Harness,
+    Input,
+    Phase,
+    Solution,
+    StrDay,
+};
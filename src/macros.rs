@@ -1,27 +1,123 @@
+/// Declares the modules of the days, from their paths, and `build_solutions`, which registers them, along with the
+/// named inputs of each day and the files of their expected answers, if any. Called by the code `build.rs`
+/// generates from the directories of the days, each of which is only compiled with its `dayNN` cfg.
 #[macro_export]
 macro_rules! aoc {
-    ($($day:ident),+ $(,)?) => {
-        $(pub mod $day;)*
+    (@answer) => { None };
+    (@answer $path:literal) => {
+        Some(|| match include_str!($path).parse::<$crate::Answer>() {
+            Ok(answer) => answer,
+            Err(e) => match e {},
+        })
+    };
+    ($($day:ident = $path:literal [$($name:literal = $text:literal [$($part1:literal)?] [$($part2:literal)?]),*]),*
+        $(,)?) => {
+        $(
+            #[cfg($day)]
+            #[path = $path]
+            pub mod $day;
+        )*
+        #[allow(unused_mut)]
         pub fn build_solutions() -> std::collections::BTreeMap<String, Solution> {
             let mut solutions = std::collections::BTreeMap::new();
-            $(solutions.insert(stringify!($day).to_owned(), $day::SOLUTION);)*
+            $(
+                #[cfg($day)]
+                solutions.insert(stringify!($day).to_owned(), $day::SOLUTION);
+            )*
             solutions
         }
+
+        /// Named inputs of `day`, which `solution!` registers after its `input` file.
+        #[doc(hidden)]
+        pub const fn named_inputs(day: u8) -> &'static [Input] {
+            match day {
+                $(
+                    #[cfg($day)]
+                    day if day == day_of_module(stringify!($day)) => {
+                        const INPUTS: &[Input] = &[$(Input {
+                            name:     $name,
+                            text:     include_str!($text),
+                            expected: [$crate::aoc!(@answer $($part1)?), $crate::aoc!(@answer $($part2)?)],
+                        }),*];
+                        INPUTS
+                    }
+                )*
+                _ => &[],
+            }
+        }
     };
 }
 
+/// Registers the solution of the current day.
+///
+/// `solution!(part1 => 54331, part2 => 54518)` registers the functions `part1` and `part2`, which take the raw
+/// input. An answer may be left out until it is known, e.g. `solution!(part1 => 54331, part2)`. Known answers are
+/// checked by `cargo test` and by the runner.
+///
+/// With a leading `parse: parse_fn,` the input is parsed once by `parse_fn` and both parts take a reference to
+/// its output instead. A day may also be given as any value implementing [`Day`](crate::Day), as in
+/// `solution!(day: Day11, part1 => 42, part2)`. Each form may start with the puzzle's `title: "...",`.
+///
+/// The answers above are those of the `input` file of the day. Other inputs, e.g. of other accounts, are found by
+/// `build.rs` in `inputs/<name>.txt`, with their answers in `inputs/<name>.part1` and `inputs/<name>.part2`, and
+/// registered after it.
+///
+/// A generator of synthetic inputs, see [`Solution::generate`](crate::Solution::generate), comes last, as in
+/// `generate: generate`.
 #[macro_export]
 macro_rules! solution {
-    ($part1:ident => $expected1:expr, $part2:ident => $expected2:expr) => {
+    (@expected $part:ident [$day:expr]) => { None };
+    (@expected $part:ident [$day:expr] $expected:expr) => { Some(|| $crate::$part(&$day, $expected)) };
+    (@generate) => { None };
+    (@generate $generate:ident) => { Some($generate) };
+    (@title [$title:expr] day: $day:expr, $($rest:tt)*) => {
+        $crate::solution!(@register [$day] $($rest)*);
+    };
+    (@title [$title:expr] parse: $parse:ident, $part1:ident $(=> $expected1:expr)?, $part2:ident $(=> $expected2:expr)?
+        $(, generate: $generate:ident)? $(,)?) => {
+        $crate::solution!(@register [$crate::FnDay {
+            day:   $crate::day_of_module(module_path!()),
+            title: $title,
+            parse: $parse,
+            part1: |input| $part1(input),
+            part2: |input| $part2(input),
+        }] $part1 $(=> $expected1)?, $part2 $(=> $expected2)? $(, generate: $generate)?);
+    };
+    (@title [$title:expr] $part1:ident $(=> $expected1:expr)?, $part2:ident $(=> $expected2:expr)?
+        $(, generate: $generate:ident)? $(,)?) => {
+        $crate::solution!(@register [$crate::StrDay {
+            day:   $crate::day_of_module(module_path!()),
+            title: $title,
+            part1: $part1,
+            part2: $part2,
+        }] $part1 $(=> $expected1)?, $part2 $(=> $expected2)? $(, generate: $generate)?);
+    };
+    (@register [$day:expr] $part1:ident $(=> $expected1:expr)?, $part2:ident $(=> $expected2:expr)?
+        $(, generate: $generate:ident)? $(,)?) => {
         pub(super) const SOLUTION: $crate::Solution = $crate::Solution {
-            part1: |input| $part1(input).map(|x| x.to_string()),
-            part2: |input| $part2(input).map(|x| x.to_string()),
-            input: include_str!("input"),
+            day:      &$day,
+            source:   file!(),
+            generate: $crate::solution!(@generate $($generate)?),
+            inputs:   &$crate::with_named_inputs::<
+                { 1 + $crate::named_inputs($crate::day_of_module(module_path!())).len() },
+            >(
+                $crate::Input {
+                    name:     "input",
+                    text:     include_str!("input"),
+                    expected: [
+                        $crate::solution!(@expected expected_part1 [$day] $($expected1)?),
+                        $crate::solution!(@expected expected_part2 [$day] $($expected2)?),
+                    ],
+                },
+                $crate::day_of_module(module_path!()),
+            ),
         };
         #[cfg(test)]
         mod test {
-            $crate::solution_test!(part1 => $expected1);
-            $crate::solution_test!(part2 => $expected2);
+            $crate::solution_test!(part1, 1);
+            $crate::solution_test!(part2, 2);
+            $crate::solution_test!(examples);
+            $crate::solution_test!(generated);
         }
         #[cfg(test)]
         mod bench {
@@ -29,6 +125,8 @@ macro_rules! solution {
             $crate::solution_bench!(part2);
         }
     };
+    (title: $title:expr, $($rest:tt)*) => { $crate::solution!(@title [$title] $($rest)*); };
+    ($($rest:tt)*) => { $crate::solution!(@title [""] $($rest)*); };
 }
 
 #[macro_export]
@@ -1,45 +1,192 @@
-use aoc2023::{build_solutions, Result, Solution};
-use std::time::{Duration, Instant};
+#![cfg_attr(not(feature = "alloc-stats"), forbid(unsafe_code))]
+mod fetch;
+mod runner;
+mod scaffold;
+
+use anyhow::Context;
+use aoc2023::{bail, build_solutions, Answer, Itertools, Result, Rng, Solution};
+use runner::{Counting, Format, Growth, Options, Recorder, Reporter};
+use std::{io::Read, path::Path, time::Duration};
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
 
 fn main() {
     if let Err(e) = try_main() {
-        eprintln!("Error: {}", e);
+        eprintln!("Error: {:#}", e);
         std::process::exit(1);
     }
 }
 
+fn read_input(path: &str) -> Result<String> {
+    let mut input = String::new();
+    match path {
+        "-" => std::io::stdin()
+            .read_to_string(&mut input)
+            .context("failed to read input from stdin")?,
+        _ => std::fs::File::open(path)
+            .and_then(|mut f| f.read_to_string(&mut input))
+            .with_context(|| format!("failed to read input from {path}"))?,
+    };
+    Ok(input)
+}
+
+/// One day to run on one input.
+struct Run {
+    day:      String,
+    solution: Solution,
+    /// Name of the input.
+    input:    String,
+    text:     String,
+    expected: [Option<Answer>; 2],
+    parts:    Vec<u8>,
+    /// Size of a generated input.
+    size:     Option<usize>,
+}
+
 fn try_main() -> Result<()> {
-    let mut solutions = build_solutions();
-    if let Some(idx) = std::env::args().nth(1) {
-        solutions.retain(|k, _| *k == idx)
+    let mut args = std::env::args().skip(1).peekable();
+    if args.next_if_eq("new").is_some() {
+        let opts = scaffold::NewOptions::parse(args)?;
+        let fetcher = opts.fetch.then(fetch::from_env).transpose()?;
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let dir = scaffold::new_day(root, &opts, fetcher.as_deref())?;
+        println!("Created {}", dir.display());
+        if let Some(line) = scaffold::missing_feature(root, opts.day)? {
+            println!("To build it on its own, add `{line}` to the [features] of Cargo.toml");
+        }
+        return Ok(());
+    }
+    let opts = Options::parse(args)?;
+    if opts.allocs {
+        Counting::enable();
     }
 
-    let mut total = Duration::from_secs(0);
-    for (idx, Solution { part1, part2, input }) in solutions {
-        println!("[{}]", idx);
+    let solutions = build_solutions();
+    let days: Vec<_> = solutions.keys().map(|idx| runner::parse_day(idx)).try_collect()?;
+    opts.select.check(&days)?;
+    opts.exclude.check(&days)?;
 
-        let (t, result) = measure(|| part1(input));
-        total += t;
-        println!("part 1: {:10} - time: {:?}", result?, t);
+    let plan = solutions
+        .into_iter()
+        .zip(days)
+        .filter_map(|((idx, solution), day)| {
+            let parts = [1, 2].into_iter().filter(|&part| opts.wants(day, part)).collect_vec();
+            (!parts.is_empty()).then_some((idx, solution, parts))
+        })
+        .collect_vec();
 
-        let (t, result) = measure(|| part2(input));
-        total += t;
-        println!("part 2: {:10} - time: {:?}", result?, t);
+    let custom_input = match &opts.input {
+        Some(path) if plan.len() != 1 => bail!("--input {path} requires exactly one day to be selected"),
+        Some(path) => Some((path.clone(), read_input(path)?)),
+        None => None,
+    };
 
-        println!();
+    // Each day runs on every embedded input, on its examples, on generated inputs, or on the custom input only.
+    let mut runs = vec![];
+    for (day, solution, parts) in plan {
+        let inputs = match (&custom_input, opts.scale) {
+            // Expected answers only hold for the embedded inputs.
+            (Some((path, text)), _) => vec![(path.clone(), text.clone(), [None, None], None)],
+            (None, _) if opts.examples => solution
+                .examples()?
+                .into_iter()
+                .map(|example| {
+                    (
+                        format!("example {}", example.name),
+                        example.text,
+                        example.expected,
+                        None,
+                    )
+                })
+                .collect_vec(),
+            (None, Some(max)) => match solution.generate {
+                Some(generate) => runner::sizes(max)
+                    .into_iter()
+                    .map(|size| {
+                        let text = generate(size, &mut Rng::new(size as u64));
+                        (format!("size {size}"), text, [None, None], Some(size))
+                    })
+                    .collect_vec(),
+                None => {
+                    eprintln!("Skipping {day}: it has no input generator");
+                    vec![]
+                }
+            },
+            (None, None) => solution
+                .inputs
+                .iter()
+                .map(|input| {
+                    (
+                        input.name.to_owned(),
+                        input.text.to_owned(),
+                        [1, 2].map(|part| input.expected(part)),
+                        None,
+                    )
+                })
+                .collect_vec(),
+        };
+        runs.extend(inputs.into_iter().map(|(input, text, expected, size)| {
+            Run {
+                day: day.clone(),
+                solution,
+                input,
+                text,
+                // Examples are often meant for one part only, and only those with an answer are run.
+                parts: match opts.examples {
+                    true => parts
+                        .iter()
+                        .copied()
+                        .filter(|&part| expected[usize::from(part) - 1].is_some())
+                        .collect(),
+                    false => parts.clone(),
+                },
+                expected,
+                size,
+            }
+        }));
     }
 
-    println!("Total time: {:?}", total);
-
-    Ok(())
-}
+    let mut reporter = Reporter::new(std::io::stdout().lock(), opts.format);
+    let mut total = Duration::from_secs(0);
+    let mut failures = 0;
+    let mut growth = Growth::default();
+    let run = |run: &Run| {
+        let Run { day, solution, input, text, expected, parts, size } = run;
+        let recorder = Recorder::new(
+            day.clone(),
+            solution.day.title(),
+            input.clone(),
+            opts.sampling,
+            expected.clone(),
+        );
+        (
+            *size,
+            recorder.run(*solution, text.clone(), parts.clone(), opts.timeout),
+        )
+    };
+    runner::run_ordered(runs, opts.jobs, run, |(size, records)| {
+        for record in records {
+            total += record.stats.median;
+            failures += record.failed() as usize;
+            if let Some(size) = size {
+                growth.add(size, &record);
+            }
+            reporter.record(&record)?;
+        }
+        Ok(())
+    })?;
+    reporter.finish(total)?;
+    // Keeps the output of the machine-readable formats parseable.
+    match (opts.scale, opts.format) {
+        (None, _) => {}
+        (Some(_), Format::Text) => growth.write(std::io::stdout().lock())?,
+        (Some(_), _) => growth.write(std::io::stderr().lock())?,
+    }
 
-#[inline]
-fn measure<T, F>(f: F) -> (Duration, T)
-where
-    F: FnOnce() -> T,
-{
-    let now = Instant::now();
-    let r = f();
-    (now.elapsed(), r)
+    match failures {
+        0 => Ok(()),
+        n => bail!("{n} failure(s)"),
+    }
 }
@@ -0,0 +1,204 @@
+//! Number theory on `usize`, with intermediate results in 128 bits: results that do not fit are errors rather than
+//! wrapped or truncated values.
+use crate::*;
+
+pub fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Greatest common divisor of all of `nums`, 0 if there are none.
+pub fn gcd_all(nums: &[usize]) -> usize {
+    nums.iter().fold(0, |acc, &x| gcd(acc, x))
+}
+
+/// Least common multiple of `a` and `b`, 0 if either is 0.
+pub fn lcm(a: usize, b: usize) -> Result<usize> {
+    match gcd(a, b) {
+        0 => Ok(0),
+        g => (a / g)
+            .checked_mul(b)
+            .ok_or_else(|| anyhow!("lcm of {} and {} overflows", a, b)),
+    }
+}
+
+/// Least common multiple of all of `nums`, 1 if there are none.
+pub fn lcm_all(nums: &[usize]) -> Result<usize> {
+    nums.iter().try_fold(1, |acc, &x| lcm(acc, x))
+}
+
+/// `(g, x, y)` such that `g = gcd(a, b) = a * x + b * y`, with `|x| <= |b| / g` and `|y| <= |a| / g` unless `a` or
+/// `b` is 0.
+pub fn extended_gcd(a: isize, b: isize) -> (usize, isize, isize) {
+    let (g, x, y) = euclid(a as i128, b as i128);
+    // Given their bounds, the coefficients fit in an `isize`, and the gcd in a `usize`.
+    (g as usize, x as isize, y as isize)
+}
+
+/// Extended Euclid on operands of up to 64 bits, whose coefficients then stay within their bounds: `(g, x, y)` as
+/// in [`extended_gcd`], with `g` not negative.
+fn euclid(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1, mut y0, mut y1) = (1i128, 0i128, 0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+    match r0 < 0 {
+        true => (-r0, -x0, -y0),
+        false => (r0, x0, y0),
+    }
+}
+
+/// `base^exp mod m`.
+pub fn mod_pow(base: usize, mut exp: usize, m: usize) -> Result<usize> {
+    ensure!(m != 0, "modulus is 0");
+    let m = m as u128;
+    let (mut base, mut acc) = (base as u128 % m, 1 % m);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = acc * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    Ok(acc as usize)
+}
+
+/// The `x` in `0..m` such that `a * x = 1 mod m`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: usize, m: usize) -> Result<usize> {
+    ensure!(m != 0, "modulus is 0");
+    let (g, x, _) = euclid((a % m) as i128, m as i128);
+    ensure!(g == 1, "{} has no inverse modulo {}", a, m);
+    Ok(x.rem_euclid(m as i128) as usize)
+}
+
+/// Solves the system of congruences `x = r mod m` for each `(r, m)` of `congruences`, whose moduli need not be
+/// coprime: `Some((x, lcm))` with `x` the smallest solution and `lcm` the lcm of the moduli, or `None` if the
+/// congruences contradict each other. The lcm must fit in a `usize`.
+pub fn crt(congruences: &[(usize, usize)]) -> Result<Option<(usize, usize)>> {
+    let (mut x, mut m) = (0u128, 1u128);
+    for &(r2, m2) in congruences {
+        ensure!(m2 != 0, "modulus is 0");
+        let (r2, m2) = (r2 as u128 % m2 as u128, m2 as u128);
+        // Both are below `m2`, which came from a `usize`.
+        let (g, inv, _) = euclid((m % m2) as i128, m2 as i128);
+        let diff = r2 as i128 - (x % m2) as i128;
+        if diff % g != 0 {
+            return Ok(None);
+        }
+        let step = m2 / g as u128;
+        let lcm = m * step;
+        ensure!(
+            lcm <= usize::MAX as u128,
+            "lcm of the moduli overflows, {} and {} at least",
+            m,
+            m2
+        );
+        // `x + m * k` is `r2 mod m2` for `k = (diff / g) * (m / g)^-1 mod m2 / g`, and `m * k < lcm`.
+        let k = (diff / g).rem_euclid(step as i128) as u128 * inv.rem_euclid(step as i128) as u128 % step;
+        (x, m) = ((x + m * k) % lcm, lcm);
+    }
+    Ok(Some((x as usize, m as usize)))
+}
+
+/// The largest `r` such that `r * r <= n`. Exact for all `n`, unlike going through `f64`.
+pub fn isqrt_floor(n: usize) -> usize {
+    n.isqrt()
+}
+
+/// The smallest `r` such that `r * r >= n`.
+pub fn isqrt_ceil(n: usize) -> usize {
+    let root = n.isqrt();
+    match root * root == n {
+        true => root,
+        false => root + 1,
+    }
+}
+
+/// The square root of `n` if it is a perfect square.
+pub fn exact_sqrt(n: usize) -> Option<usize> {
+    let root = n.isqrt();
+    (root * root == n).then_some(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_lcm() -> Result<()> {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd_all(&[12, 18, 8]), 2);
+        assert_eq!(gcd_all(&[]), 0);
+        assert_eq!(lcm(4, 6)?, 12);
+        assert_eq!(lcm(0, 6)?, 0);
+        assert_eq!(lcm_all(&[2, 3, 4, 5])?, 60);
+        assert_eq!(lcm_all(&[])?, 1);
+        assert!(lcm(usize::MAX, usize::MAX - 1).is_err());
+        assert!(lcm_all(&[1 << 62, 3, 5]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn euclid() {
+        for (a, b) in [
+            (240, 46),
+            (-240, 46),
+            (7, 0),
+            (0, -7),
+            (17, 5),
+            (isize::MAX, isize::MAX - 1),
+        ] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a.unsigned_abs(), b.unsigned_abs()));
+            assert_eq!(a as i128 * x as i128 + b as i128 * y as i128, g as i128);
+        }
+    }
+
+    #[test]
+    fn modular() -> Result<()> {
+        assert_eq!(mod_pow(4, 13, 497)?, 445);
+        assert_eq!(mod_pow(5, 0, 1)?, 0);
+        assert_eq!(mod_pow(usize::MAX, usize::MAX, usize::MAX - 1)?, 1);
+        assert!(mod_pow(2, 2, 0).is_err());
+        assert_eq!(mod_inverse(3, 11)?, 4);
+        assert_eq!(mod_inverse(usize::MAX - 1, usize::MAX)?, usize::MAX - 1);
+        assert!(mod_inverse(4, 8).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn chinese_remainder() -> Result<()> {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)])?, Some((23, 105)));
+        // Moduli sharing factors.
+        assert_eq!(crt(&[(3, 4), (5, 6)])?, Some((11, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)])?, None);
+        assert_eq!(crt(&[(10, 3)])?, Some((1, 3)));
+        assert_eq!(crt(&[])?, Some((0, 1)));
+        let big = (1 << 61) - 1; // A prime.
+        assert_eq!(crt(&[(1, big), (0, 2)])?, Some((big + 1, 2 * big)));
+        assert!(crt(&[(1, big), (0, 17)]).is_err());
+        assert!(crt(&[(1, 0)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn sqrt() {
+        assert_eq!(exact_sqrt(0), Some(0));
+        assert_eq!(exact_sqrt(144), Some(12));
+        assert_eq!(exact_sqrt(145), None);
+        let root = (1 << 32) - 1;
+        assert_eq!(exact_sqrt(root * root), Some(root));
+        assert_eq!(exact_sqrt(root * root - 1), None);
+        assert_eq!((isqrt_floor(15), isqrt_ceil(15)), (3, 4));
+        assert_eq!((isqrt_floor(16), isqrt_ceil(16)), (4, 4));
+        assert_eq!((isqrt_floor(0), isqrt_ceil(0)), (0, 0));
+        assert_eq!((isqrt_floor(usize::MAX), isqrt_ceil(usize::MAX)), (root, root + 1));
+    }
+}
@@ -0,0 +1,432 @@
+//! Parsers of pieces of text, combined into parsers of whole inputs. When an input does not match, the error tells
+//! the line and column where it stopped matching, and what was expected there.
+//!
+//! A parser that fails without consuming anything lets the combinators above it try something else: a list ends
+//! when no item follows its last separator, and [`opt`] gives `None`. Once a parser has consumed some input, its
+//! failure is final.
+use crate::*;
+use std::fmt;
+
+/// What a parser expected to find.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wanted {
+    /// This exact text.
+    Literal(&'static str),
+    /// A kind of token, described like `an integer`.
+    Token(&'static str),
+}
+
+impl fmt::Display for Wanted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Wanted::Literal(text) => write!(f, "{text:?}"),
+            Wanted::Token(what) => f.write_str(what),
+        }
+    }
+}
+
+/// Why a parser failed: what it expected at an offset of the input. It also carries the farthest failure that
+/// the parsers before it recovered from, which is often the actual mistake: when the last item of a list does not
+/// parse, the list ends early, and the parser after the list fails instead.
+#[derive(Clone, Copy, Debug)]
+pub struct Failure {
+    offset:   usize,
+    expected: Wanted,
+    farthest: Option<(usize, Wanted)>,
+}
+
+/// The input being parsed, from some offset on.
+#[derive(Clone, Copy, Debug)]
+pub struct Cursor<'a> {
+    input:    &'a str,
+    offset:   usize,
+    farthest: Option<(usize, Wanted)>,
+}
+
+pub type PResult<'a, T> = Result<(T, Cursor<'a>), Failure>;
+
+impl<'a> Cursor<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, offset: 0, farthest: None }
+    }
+
+    /// The input left to parse.
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.offset..]
+    }
+
+    /// Skips `len` bytes, which must end on a character boundary.
+    pub fn advance(self, len: usize) -> Self {
+        Self { offset: self.offset + len, ..self }
+    }
+
+    pub fn fail<T>(self, expected: Wanted) -> PResult<'a, T> {
+        Err(self.failure(expected))
+    }
+
+    fn failure(self, expected: Wanted) -> Failure {
+        Failure { offset: self.offset, expected, farthest: self.farthest }
+    }
+
+    /// Remembers `failure`, which a parser recovered from, if it is the farthest so far.
+    fn recover(mut self, failure: Failure) -> Self {
+        for (offset, expected) in [Some((failure.offset, failure.expected)), failure.farthest]
+            .into_iter()
+            .flatten()
+        {
+            if self.farthest.is_none_or(|(farthest, _)| offset > farthest) {
+                self.farthest = Some((offset, expected));
+            }
+        }
+        self
+    }
+
+    /// Line and column of `offset`, both counted from 1, the column in characters.
+    fn location(&self, offset: usize) -> (usize, usize) {
+        let before = &self.input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+        )
+    }
+
+    fn error(&self, offset: usize, expected: Wanted) -> ParseError {
+        const SHOWN: usize = 16;
+        let (line, column) = self.location(offset);
+        let rest = &self.input[offset..];
+        // The rest of the line, without its line break, so that a `\r` of its own still shows.
+        let rest_of_line = rest.split('\n').next().unwrap_or_default();
+        let text = match rest.len() > rest_of_line.len() {
+            true => rest_of_line.strip_suffix('\r').unwrap_or(rest_of_line),
+            false => rest_of_line,
+        };
+        let found = match text {
+            "" if rest.is_empty() => "end of input".to_owned(),
+            "" => "end of line".to_owned(),
+            text => match text.char_indices().nth(SHOWN) {
+                Some((end, _)) => format!("{:?}...", &text[..end]),
+                None => format!("{text:?}"),
+            },
+        };
+        ParseError { line, column, expected, found }
+    }
+}
+
+/// An input that does not match a parser, located.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line:     usize,
+    pub column:   usize,
+    pub expected: Wanted,
+    /// The start of the text found instead, or `end of line` or `end of input`.
+    pub found:    String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ParseError { line, column, expected, found } = self;
+        write!(f, "line {line}, column {column}: expected {expected}, found {found}")
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub trait Parser<'a, T> {
+    fn parse(&self, input: Cursor<'a>) -> PResult<'a, T>;
+
+    fn map<U>(self, f: impl Fn(T) -> U) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+    {
+        move |input: Cursor<'a>| self.parse(input).map(|(value, rest)| (f(value), rest))
+    }
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: Fn(Cursor<'a>) -> PResult<'a, T>,
+{
+    fn parse(&self, input: Cursor<'a>) -> PResult<'a, T> {
+        self(input)
+    }
+}
+
+/// Parses the whole of `input` with `parser`, except for trailing whitespace.
+pub fn parse_all<'a, T>(input: &'a str, parser: impl Parser<'a, T>) -> Result<T> {
+    let start = Cursor::new(input);
+    let (value, rest) = parser.parse(start).map_err(|failure| located(start, failure))?;
+    match rest.rest().trim_start() {
+        "" => Ok(value),
+        _ => Err(located(start, rest.failure(Wanted::Token("end of input"))).into()),
+    }
+}
+
+/// The error for `failure`, located at the farthest of the failures it carries.
+fn located(start: Cursor, failure: Failure) -> ParseError {
+    match failure.farthest {
+        Some((offset, expected)) if offset > failure.offset => start.error(offset, expected),
+        _ => start.error(failure.offset, failure.expected),
+    }
+}
+
+/// Parses `len` bytes of `input` as a number.
+fn number<'a, T: FromStr>(input: Cursor<'a>, len: usize, what: &'static str) -> PResult<'a, T> {
+    match input.rest()[..len].parse() {
+        Ok(n) => Ok((n, input.advance(len))),
+        Err(_) if len == 0 || !input.rest()[..len].ends_with(|c: char| c.is_ascii_digit()) =>
+            input.fail(Wanted::Token(what)),
+        Err(_) => input.fail(Wanted::Token("a number small enough")),
+    }
+}
+
+/// An unsigned decimal integer.
+pub fn uint<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: Cursor<'a>| {
+        let len = input.rest().bytes().take_while(u8::is_ascii_digit).count();
+        number(input, len, "an unsigned integer")
+    }
+}
+
+/// A decimal integer, with an optional sign.
+pub fn int<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: Cursor<'a>| {
+        let sign = usize::from(input.rest().starts_with(['-', '+']));
+        let len = sign + input.rest()[sign..].bytes().take_while(u8::is_ascii_digit).count();
+        number(input, len, "an integer")
+    }
+}
+
+pub fn literal<'a>(text: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: Cursor<'a>| match input.rest().starts_with(text) {
+        true => Ok((&input.rest()[..text.len()], input.advance(text.len()))),
+        false => input.fail(Wanted::Literal(text)),
+    }
+}
+
+/// One or more ASCII letters and digits.
+pub fn word<'a>() -> impl Parser<'a, &'a str> {
+    move |input: Cursor<'a>| match input.rest().bytes().take_while(u8::is_ascii_alphanumeric).count() {
+        0 => input.fail(Wanted::Token("a word")),
+        len => Ok((&input.rest()[..len], input.advance(len))),
+    }
+}
+
+/// One or more spaces.
+pub fn spaces<'a>() -> impl Parser<'a, ()> {
+    move |input: Cursor<'a>| match input.rest().bytes().take_while(|&c| c == b' ').count() {
+        0 => input.fail(Wanted::Token("a space")),
+        len => Ok(((), input.advance(len))),
+    }
+}
+
+/// The value of the first of `options` whose text comes next, `what` describing them all in errors.
+pub fn one_of<'a, T: Copy>(options: &'static [(&'static str, T)], what: &'static str) -> impl Parser<'a, T> {
+    move |input: Cursor<'a>| match options.iter().find(|(text, _)| input.rest().starts_with(text)) {
+        Some(&(text, value)) => Ok((value, input.advance(text.len()))),
+        None => input.fail(Wanted::Token(what)),
+    }
+}
+
+pub fn pair<'a, A, B>(first: impl Parser<'a, A>, second: impl Parser<'a, B>) -> impl Parser<'a, (A, B)> {
+    move |input: Cursor<'a>| {
+        let (a, input) = first.parse(input)?;
+        let (b, input) = second.parse(input)?;
+        Ok(((a, b), input))
+    }
+}
+
+/// `prefix`, then `value`, whose result only is kept.
+pub fn preceded<'a, P, T>(prefix: impl Parser<'a, P>, value: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    pair(prefix, value).map(|(_, value)| value)
+}
+
+/// `value`, then `suffix`, whose result is dropped.
+pub fn terminated<'a, T, S>(value: impl Parser<'a, T>, suffix: impl Parser<'a, S>) -> impl Parser<'a, T> {
+    pair(value, suffix).map(|(value, _)| value)
+}
+
+/// `parser`, or `None` if it fails without consuming anything.
+pub fn opt<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Option<T>> {
+    move |input: Cursor<'a>| match parser.parse(input) {
+        Ok((value, rest)) => Ok((Some(value), rest)),
+        Err(failure) if failure.offset == input.offset => Ok((None, input.recover(failure))),
+        Err(failure) => Err(failure),
+    }
+}
+
+/// One or more `item`s separated by `separator`s. The list ends before a separator that no item follows.
+pub fn separated<'a, T, S>(item: impl Parser<'a, T>, separator: impl Parser<'a, S>) -> impl Parser<'a, Vec<T>> {
+    move |input: Cursor<'a>| {
+        let (first, mut input) = item.parse(input)?;
+        let mut items = vec![first];
+        loop {
+            let after = match separator.parse(input) {
+                Ok((_, after)) => after,
+                Err(failure) if failure.offset == input.offset => return Ok((items, input.recover(failure))),
+                Err(failure) => return Err(failure),
+            };
+            match item.parse(after) {
+                Ok((value, rest)) => {
+                    items.push(value);
+                    input = rest;
+                }
+                Err(failure) if failure.offset == after.offset => return Ok((items, input.recover(failure))),
+                Err(failure) => return Err(failure),
+            };
+        }
+    }
+}
+
+/// Length of the line break at the start of `text`, if any: `\n` or `\r\n`, after optional trailing spaces.
+fn line_break(text: &str) -> Option<usize> {
+    let after = text.trim_start_matches(' ');
+    let after = after.strip_prefix('\r').unwrap_or(after);
+    after.starts_with('\n').then(|| text.len() - after.len() + 1)
+}
+
+/// The end of a line, `\n` or `\r\n`, after optional trailing spaces.
+pub fn line_end<'a>() -> impl Parser<'a, ()> {
+    move |input: Cursor<'a>| match line_break(input.rest()) {
+        Some(len) => Ok(((), input.advance(len))),
+        None => input.fail(Wanted::Token("end of line")),
+    }
+}
+
+/// A blank line, after the end of the line before it.
+pub fn blank_line<'a>() -> impl Parser<'a, ()> {
+    move |input: Cursor<'a>| match line_break(input.rest()) {
+        Some(first) => match line_break(&input.rest()[first..]) {
+            Some(second) => Ok(((), input.advance(first + second))),
+            None => input.fail(Wanted::Token("a blank line")),
+        },
+        None => input.fail(Wanted::Token("a blank line")),
+    }
+}
+
+/// One `item` per line.
+pub fn lines<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    separated(item, line_end())
+}
+
+/// Blocks of lines separated by a blank line, parsed with `block`.
+pub fn blocks<'a, T>(block: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    separated(block, blank_line())
+}
+
+/// A header like `Game 3: `, made of `name`, spaces, a value parsed with `value`, and a colon, followed by
+/// optional spaces.
+pub fn header<'a, T>(name: &'static str, value: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    terminated(
+        preceded(pair(literal(name), spaces()), value),
+        pair(literal(":"), opt(spaces())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error<'a, T: fmt::Debug>(input: &'a str, parser: impl Parser<'a, T>) -> String {
+        match parse_all(input, parser) {
+            Ok(value) => format!("parsed {value:?}"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn numbers() -> Result<()> {
+        assert_eq!(parse_all("42", uint::<u8>())?, 42);
+        assert_eq!(parse_all("-42", int::<i64>())?, -42);
+        assert_eq!(parse_all("+7\n", int::<i64>())?, 7);
+        assert_eq!(
+            error("-42", uint::<u8>()),
+            "line 1, column 1: expected an unsigned integer, found \"-42\""
+        );
+        assert_eq!(
+            error("300", uint::<u8>()),
+            "line 1, column 1: expected a number small enough, found \"300\""
+        );
+        assert_eq!(
+            error("-", int::<i8>()),
+            "line 1, column 1: expected an integer, found \"-\""
+        );
+        assert_eq!(
+            error("", int::<i8>()),
+            "line 1, column 1: expected an integer, found end of input"
+        );
+        assert_eq!(
+            error("12x", uint::<u8>()),
+            "line 1, column 3: expected end of input, found \"x\""
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lists() -> Result<()> {
+        let list = || separated(int::<i32>(), literal(", "));
+        assert_eq!(parse_all("1, -2, 3", list())?, [1, -2, 3]);
+        assert_eq!(parse_all("1\n2\n\n3\n", blocks(lines(uint::<u8>())))?, [
+            vec![1, 2],
+            vec![3]
+        ]);
+        assert_eq!(parse_all("ab cd", separated(word(), spaces()))?, ["ab", "cd"]);
+        assert_eq!(parse_all("5", pair(opt(literal("-")), uint::<u8>()))?, (None, 5));
+        // The list ends before the last separator, but the error is where the item is missing.
+        assert_eq!(
+            error("1, 2, x", list()),
+            "line 1, column 7: expected an integer, found \"x\""
+        );
+        assert_eq!(
+            error("1\n2\nthree four five six seven\n", lines(uint::<u8>())),
+            "line 3, column 1: expected an unsigned integer, found \"three four five \"..."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn line_breaks() -> Result<()> {
+        let numbers = || blocks(lines(separated(uint::<u8>(), spaces())));
+        let expected = [vec![vec![1, 2], vec![3]], vec![vec![4]]];
+        assert_eq!(parse_all("1 2\r\n3\r\n\r\n4\r\n", numbers())?, expected);
+        assert_eq!(parse_all("1 2  \n3 \n  \n4  ", numbers())?, expected);
+        assert_eq!(
+            error("1 2\r\n3\rx\n", numbers()),
+            "line 2, column 2: expected end of input, found \"\\rx\""
+        );
+        assert_eq!(
+            error("1\r\nx\r\n", numbers()),
+            "line 2, column 1: expected an unsigned integer, found \"x\""
+        );
+        assert_eq!(
+            error("1\r\n\r\nx", numbers()),
+            "line 3, column 1: expected an unsigned integer, found \"x\""
+        );
+        assert_eq!(
+            error("1 x\r\n", numbers()),
+            "line 1, column 3: expected an unsigned integer, found \"x\""
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn headers() -> Result<()> {
+        const COLORS: &[(&str, u8)] = &[("red", 0), ("green", 1)];
+        let cube = || pair(terminated(uint::<u32>(), spaces()), one_of(COLORS, "a colour"));
+        let game = || pair(header("Game", uint::<u32>()), separated(cube(), literal(", ")));
+        assert_eq!(parse_all("Game  3: 1 red, 2 green", game())?, (3, vec![(1, 0), (2, 1)]));
+        assert_eq!(
+            error("Game 3: 1 red, 2 blue", game()),
+            "line 1, column 18: expected a colour, found \"blue\""
+        );
+        assert_eq!(
+            error("Game 3 1 red", game()),
+            "line 1, column 7: expected \":\", found \" 1 red\""
+        );
+        assert_eq!(
+            error("Card 3: 1 red", game()),
+            "line 1, column 1: expected \"Game\", found \"Card 3: 1 red\""
+        );
+        Ok(())
+    }
+}
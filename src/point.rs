@@ -0,0 +1,163 @@
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+/// One of the 4 orthogonal directions, on a grid whose rows go down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions, clockwise from `Up`.
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    /// Change of `(row, col)` of a step in this direction.
+    pub const fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Right => (0, 1),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+        }
+    }
+
+    /// A quarter turn counter-clockwise.
+    pub const fn turn_left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    /// A quarter turn clockwise.
+    pub const fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub const fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+        }
+    }
+}
+
+/// A position on a grid, ordered row by row. Coordinates are unsigned: moves that would make them negative fail
+/// instead of wrapping around.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Point {
+    pub const fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// The point `(drow, dcol)` away, if its coordinates are not negative and do not overflow.
+    pub fn offset(self, (drow, dcol): (isize, isize)) -> Option<Self> {
+        Some(Self::new(
+            self.row.checked_add_signed(drow)?,
+            self.col.checked_add_signed(dcol)?,
+        ))
+    }
+
+    /// The next point in direction `dir`, if any.
+    pub fn step(self, dir: Direction) -> Option<Self> {
+        self.offset(dir.offset())
+    }
+
+    /// The next point in direction `dir`, if it is inside `nrows` rows of `ncols` columns.
+    pub fn step_within(self, dir: Direction, nrows: usize, ncols: usize) -> Option<Self> {
+        self.step(dir).filter(|p| p.row < nrows && p.col < ncols)
+    }
+
+    pub fn manhattan(self, other: Self) -> usize {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self::new(row, col)
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.row + other.row, self.col + other.col)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+/// Panics, like `usize`, if `other` is below or right of `self`.
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl Mul<usize> for Point {
+    type Output = Self;
+
+    fn mul(self, k: usize) -> Self {
+        Self::new(self.row * k, self.col * k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction::*, *};
+
+    #[test]
+    fn directions() {
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.turn_right().turn_right(), dir.opposite());
+            let (drow, dcol) = dir.offset();
+            assert_eq!(dir.opposite().offset(), (-drow, -dcol));
+        }
+        assert_eq!(Up.turn_right(), Right);
+        assert_eq!(Up.turn_left(), Left);
+    }
+
+    #[test]
+    fn points() {
+        let p = Point::new(0, 2);
+        assert_eq!(p.step(Up), None);
+        assert_eq!(p.step(Left), Some(Point::new(0, 1)));
+        assert_eq!(p.step(Down), Some(Point::new(1, 2)));
+        assert_eq!(p.step_within(Right, 3, 3), None);
+        assert_eq!(p.step_within(Down, 3, 3), Some(Point::new(1, 2)));
+        assert_eq!(Point::new(usize::MAX, 0).step(Down), None);
+        assert_eq!(p.manhattan(Point::new(3, 0)), 5);
+
+        let mut q = Point::from((1, 1));
+        q += p;
+        assert_eq!(q, Point::new(1, 3));
+        assert_eq!(q - p, Point::new(1, 1));
+        assert_eq!(q * 2, Point::new(2, 6));
+        assert!(Point::new(0, 5) < Point::new(1, 0));
+    }
+}
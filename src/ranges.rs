@@ -0,0 +1,356 @@
+//! Sets of `usize` made of ranges, and maps shifting ranges of `usize`, computed range by range rather than number
+//! by number.
+use crate::*;
+
+/// A set of `usize`, kept normalised as sorted ranges that neither overlap nor touch each other.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RangeSet {
+    ranges: Vec<Range<usize>>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The normalised ranges of the set, in increasing order.
+    pub fn ranges(&self) -> &[Range<usize>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of elements of the set.
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|range| range.len()).sum()
+    }
+
+    pub fn min(&self) -> Option<usize> {
+        self.ranges.first().map(|range| range.start)
+    }
+
+    pub fn max(&self) -> Option<usize> {
+        self.ranges.last().map(|range| range.end - 1)
+    }
+
+    pub fn contains(&self, x: usize) -> bool {
+        let i = self.ranges.partition_point(|range| range.end <= x);
+        self.ranges.get(i).is_some_and(|range| range.contains(&x))
+    }
+
+    pub fn insert(&mut self, range: Range<usize>) {
+        self.ranges.push(range);
+        self.normalise();
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.ranges.iter().chain(&other.ranges).cloned().collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.ranges.iter().peekable(), other.ranges.iter().peekable());
+        let mut ranges = Vec::new();
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let (start, end) = (max(x.start, y.start), min(x.end, y.end));
+            if start < end {
+                ranges.push(start..end);
+            }
+            // The range ending first cannot meet any other range of the other set.
+            match x.end <= y.end {
+                true => a.next(),
+                false => b.next(),
+            };
+        }
+        // Intersecting normalised sets gives a normalised set.
+        Self { ranges }
+    }
+
+    /// Elements of `self` not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut holes = other.ranges.iter().peekable();
+        for range in &self.ranges {
+            let mut start = range.start;
+            while let Some(hole) = holes.peek() {
+                if hole.end <= start {
+                    holes.next();
+                    continue;
+                }
+                if hole.start >= range.end {
+                    break;
+                }
+                if hole.start > start {
+                    ranges.push(start..hole.start);
+                }
+                start = hole.end;
+                if hole.end > range.end {
+                    break;
+                }
+                holes.next();
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Drops empty ranges, sorts them, and merges those that overlap or touch.
+    fn normalise(&mut self) {
+        self.ranges.retain(|range| !range.is_empty());
+        self.ranges.sort_unstable_by_key(|range| range.start);
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(self.ranges.len());
+        for range in self.ranges.drain(..) {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = max(last.end, range.end),
+                _ => merged.push(range),
+            }
+        }
+        self.ranges = merged;
+    }
+}
+
+impl FromIterator<Range<usize>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Range<usize>>>(iter: I) -> Self {
+        let mut set = Self { ranges: iter.into_iter().collect() };
+        set.normalise();
+        set
+    }
+}
+
+/// A range of sources mapped to as many consecutive destinations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Piece {
+    src: Range<usize>,
+    dst: usize,
+}
+
+/// A map from `usize` to `usize` shifting each of some disjoint ranges by its own offset, and leaving the other
+/// numbers unchanged, like the maps of an almanac.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PiecewiseMap {
+    /// Sorted, non-empty and disjoint. Pieces that only extend the previous one are merged into it.
+    pieces: Vec<Piece>,
+}
+
+impl PiecewiseMap {
+    /// A map sending each range of sources to the destinations starting at the number given with it.
+    pub fn new(pieces: impl IntoIterator<Item = (Range<usize>, usize)>) -> Result<Self> {
+        let mut pieces: Vec<_> = pieces
+            .into_iter()
+            .filter(|(src, _)| !src.is_empty())
+            .map(|(src, dst)| match dst.checked_add(src.len()) {
+                Some(_) => Ok(Piece { src, dst }),
+                None => bail!("range too large: {:?} to {}", src, dst),
+            })
+            .try_collect()?;
+        pieces.sort_unstable_by_key(|piece| piece.src.start);
+        if let Some((a, b)) = pieces.iter().tuple_windows().find(|(a, b)| a.src.end > b.src.start) {
+            bail!("overlapping ranges: {:?} and {:?}", a.src, b.src);
+        }
+        Ok(Self::from_sorted(pieces))
+    }
+
+    /// Merges consecutive pieces with the same offset, and drops those mapping their sources to themselves.
+    fn from_sorted(pieces: impl IntoIterator<Item = Piece>) -> Self {
+        let mut merged: Vec<Piece> = Vec::new();
+        for piece in pieces.into_iter().filter(|piece| piece.src.start != piece.dst) {
+            match merged.last_mut() {
+                Some(last) if last.src.end == piece.src.start && last.dst + last.src.len() == piece.dst =>
+                    last.src.end = piece.src.end,
+                _ => merged.push(piece),
+            }
+        }
+        Self { pieces: merged }
+    }
+
+    pub fn get(&self, x: usize) -> usize {
+        let i = self.pieces.partition_point(|piece| piece.src.end <= x);
+        match self.pieces.get(i) {
+            Some(piece) if piece.src.contains(&x) => piece.dst + (x - piece.src.start),
+            _ => x,
+        }
+    }
+
+    /// Splits `range` into consecutive ranges each mapped as a whole, with the destinations of their starts.
+    fn segments(&self, range: Range<usize>) -> impl Iterator<Item = (Range<usize>, usize)> + '_ {
+        let first = self.pieces.partition_point(|piece| piece.src.end <= range.start);
+        let mut pieces = self.pieces[first..].iter().peekable();
+        let mut start = range.start;
+        std::iter::from_fn(move || {
+            if start >= range.end {
+                return None;
+            }
+            let segment = match pieces.peek() {
+                Some(piece) if piece.src.start <= start => {
+                    let segment = (
+                        start..min(piece.src.end, range.end),
+                        piece.dst + (start - piece.src.start),
+                    );
+                    pieces.next();
+                    segment
+                }
+                Some(piece) => (start..min(piece.src.start, range.end), start),
+                None => (start..range.end, start),
+            };
+            start = segment.0.end;
+            Some(segment)
+        })
+    }
+
+    /// Image of the numbers of `range`.
+    pub fn apply_range(&self, range: Range<usize>) -> RangeSet {
+        self.segments(range).map(|(src, dst)| dst..dst + src.len()).collect()
+    }
+
+    /// Image of the numbers of `set`.
+    pub fn apply(&self, set: &RangeSet) -> RangeSet {
+        set.ranges()
+            .iter()
+            .flat_map(|range| self.segments(range.clone()))
+            .map(|(src, dst)| dst..dst + src.len())
+            .collect()
+    }
+
+    /// The map undoing this one, if it is a bijection: its ranges of destinations must be disjoint and make up the
+    /// same set as its ranges of sources.
+    pub fn inverse(&self) -> Result<Self> {
+        let srcs: RangeSet = self.pieces.iter().map(|piece| piece.src.clone()).collect();
+        let dsts: RangeSet = self
+            .pieces
+            .iter()
+            .map(|piece| piece.dst..piece.dst + piece.src.len())
+            .collect();
+        // Overlapping destinations would make up a smaller set.
+        ensure!(
+            srcs == dsts,
+            "map is not invertible: it maps {:?} to {:?}",
+            srcs.ranges(),
+            dsts.ranges()
+        );
+        Self::new(
+            self.pieces
+                .iter()
+                .map(|piece| (piece.dst..piece.dst + piece.src.len(), piece.src.start)),
+        )
+    }
+
+    /// The map applying this one, then `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        let pieces = self.segments(0..usize::MAX).flat_map(|(src, dst)| {
+            next.segments(dst..dst + src.len()).map(move |(mid, end)| Piece {
+                src: src.start + (mid.start - dst)..src.start + (mid.end - dst),
+                dst: end,
+            })
+        });
+        Self::from_sorted(pieces)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[Range<usize>]) -> RangeSet {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn range_set() {
+        let a = set(&[5..8, 0..3, 2..4, 8..9, 12..12]);
+        assert_eq!(a.ranges(), [0..4, 5..9]);
+        assert_eq!((a.len(), a.min(), a.max()), (8, Some(0), Some(8)));
+        assert!(a.contains(3) && !a.contains(4) && a.contains(8) && !a.contains(9));
+
+        let b = set(&[2..6, 7..8, 10..20]);
+        assert_eq!(a.union(&b).ranges(), [0..9, 10..20]);
+        assert_eq!(a.intersection(&b).ranges(), [2..4, 5..6, 7..8]);
+        assert_eq!(a.difference(&b).ranges(), [0..2, 6..7, 8..9]);
+        assert_eq!(b.difference(&a).ranges(), [4..5, 10..20]);
+        assert_eq!(a.difference(&set(&[0..100])), RangeSet::new());
+
+        let mut c = RangeSet::new();
+        c.insert(3..5);
+        c.insert(0..1);
+        c.insert(1..3);
+        assert_eq!(c.ranges(), [0..5]);
+    }
+
+    #[test]
+    fn range_set_ops_match_naive() {
+        let mut rng = Rng::new(0);
+        for _ in 0..200 {
+            let mut random = || -> RangeSet {
+                (0..rng.below(5))
+                    .map(|_| {
+                        let start = rng.below(30);
+                        start..start + rng.below(8)
+                    })
+                    .collect()
+            };
+            let (a, b) = (random(), random());
+            for (op, f) in [
+                (a.union(&b), (|x, y| x || y) as fn(bool, bool) -> bool),
+                (a.intersection(&b), |x, y| x && y),
+                (a.difference(&b), |x, y| x && !y),
+            ] {
+                assert_eq!(
+                    op,
+                    (0..40)
+                        .filter(|&x| f(a.contains(x), b.contains(x)))
+                        .map(|x| x..x + 1)
+                        .collect()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn piecewise_map() -> Result<()> {
+        // The first map of the almanac example.
+        let map = PiecewiseMap::new([(98..100, 50), (50..98, 52)])?;
+        assert_eq!([0, 49, 50, 97, 98, 99, 100].map(|x| map.get(x)), [
+            0, 49, 52, 99, 50, 51, 100
+        ]);
+        assert_eq!(map.apply_range(45..100).ranges(), [45..100]);
+        assert_eq!(map.apply(&set(&[95..99])).ranges(), [50..51, 97..100]);
+
+        let inverse = map.inverse()?;
+        assert!((0..120).all(|x| inverse.get(map.get(x)) == x));
+        assert!(PiecewiseMap::new([(0..10, 5)])?.inverse().is_err());
+
+        assert!(PiecewiseMap::new([(0..10, 20), (5..15, 40)]).is_err());
+        assert!(PiecewiseMap::new([(0..10, usize::MAX - 5)]).is_err());
+        // Identity pieces, and pieces continuing each other, are merged away.
+        assert_eq!(
+            PiecewiseMap::new([(0..5, 0), (10..15, 20), (15..20, 25)])?,
+            PiecewiseMap::new([(10..20, 20)])?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn composition() -> Result<()> {
+        let mut rng = Rng::new(1);
+        let mut random = || {
+            let mut bounds = (0..6).map(|_| rng.below(50)).collect_vec();
+            bounds.sort_unstable();
+            let pieces = bounds
+                .into_iter()
+                .tuples()
+                .map(|(start, end)| (start..end, rng.below(50)))
+                .collect_vec();
+            PiecewiseMap::new(pieces)
+        };
+        for _ in 0..100 {
+            let (f, g) = (random()?, random()?);
+            let h = f.then(&g);
+            assert!((0..120).all(|x| h.get(x) == g.get(f.get(x))));
+            assert_eq!(h.apply_range(0..120), g.apply(&f.apply_range(0..120)));
+        }
+        Ok(())
+    }
+}
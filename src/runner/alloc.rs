@@ -0,0 +1,58 @@
+#[cfg(feature = "alloc-stats")]
+pub use super::counting::{measure, Counting};
+
+/// Allocations made while running a piece of work.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Allocs {
+    pub count: u64,
+    pub bytes: u64,
+    /// Most bytes live at once, on top of those live beforehand, or after freeing some of them: memory the work
+    /// frees and allocates again is only counted once.
+    pub peak:  u64,
+}
+
+/// Stands for the counting allocator without the `alloc-stats` feature: nothing is counted.
+#[cfg(not(feature = "alloc-stats"))]
+pub struct Counting;
+
+#[cfg(not(feature = "alloc-stats"))]
+impl Counting {
+    pub fn enable() {}
+
+    pub fn enabled() -> bool {
+        false
+    }
+}
+
+#[cfg(not(feature = "alloc-stats"))]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Allocs) {
+    (f(), Allocs::default())
+}
+
+/// Formats a number of bytes with a binary unit, e.g. `1.5 KiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 << 20), "3.0 MiB");
+    }
+}
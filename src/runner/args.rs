@@ -0,0 +1,132 @@
+use super::{Format, Sampling, Selection};
+use aoc2023::{anyhow, bail, ensure, Result};
+use std::time::Duration;
+
+pub struct Options {
+    /// Days and parts to run; everything when empty.
+    pub select:   Selection,
+    /// Days and parts to skip.
+    pub exclude:  Selection,
+    /// Read the puzzle input from this path (`-` for stdin) instead of the embedded one.
+    pub input:    Option<String>,
+    /// Run the examples of each day instead of its inputs.
+    pub examples: bool,
+    /// Run each day on generated inputs of growing sizes up to this one instead, to see how it scales.
+    pub scale:    Option<usize>,
+    /// How results are printed.
+    pub format:   Format,
+    /// Count the allocations of each part.
+    pub allocs:   bool,
+    /// How many times each part is run to time it.
+    pub sampling: Sampling,
+    /// How many days are run concurrently; 1 runs them one after another, which gives the most accurate timings.
+    pub jobs:     usize,
+    /// Give up on a part that runs for longer than this, and move on to the next day.
+    pub timeout:  Option<Duration>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            select:   Selection::default(),
+            exclude:  Selection::default(),
+            input:    None,
+            examples: false,
+            scale:    None,
+            format:   Format::default(),
+            allocs:   false,
+            sampling: Sampling::default(),
+            jobs:     1,
+            timeout:  None,
+        }
+    }
+}
+
+impl Options {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut opts = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_owned())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || match inline.clone().or_else(|| args.next()) {
+                Some(value) => Ok(value),
+                None => bail!("missing value for {name}"),
+            };
+            match name {
+                "-i" | "--input" => opts.input = Some(value()?),
+                "-e" | "--examples" => opts.examples = true,
+                "-s" | "--scale" => match value()?.parse()? {
+                    0 => bail!("--scale must be at least 1"),
+                    n => opts.scale = Some(n),
+                },
+                "-x" | "--exclude" => opts.exclude.extend(value()?.parse()?),
+                "-f" | "--format" => opts.format = value()?.parse()?,
+                "-a" | "--allocs" if cfg!(feature = "alloc-stats") => opts.allocs = true,
+                "-a" | "--allocs" => bail!("{name} requires the alloc-stats feature"),
+                "-r" | "--repeat" => match value()?.parse()? {
+                    0 => bail!("--repeat must be at least 1"),
+                    n => opts.sampling.repeat = n,
+                },
+                "--min-time" => opts.sampling.min_time = parse_duration(&value()?)?,
+                "-j" | "--jobs" => match value()?.parse()? {
+                    0 => opts.jobs = std::thread::available_parallelism().map_or(1, |n| n.get()),
+                    n => opts.jobs = n,
+                },
+                "-t" | "--timeout" => opts.timeout = Some(parse_duration(&value()?)?),
+                _ if name.starts_with('-') && name != "-" => bail!("unknown option: {name}"),
+                _ => opts.select.extend(arg.parse()?),
+            }
+        }
+        ensure!(
+            [opts.input.is_some(), opts.examples, opts.scale.is_some()]
+                .into_iter()
+                .filter(|&b| b)
+                .count()
+                <= 1,
+            "--input, --examples and --scale cannot be used together"
+        );
+        Ok(opts)
+    }
+}
+
+impl Options {
+    /// Whether `part` of `day` is selected and not excluded.
+    pub fn wants(&self, day: u32, part: u8) -> bool {
+        (self.select.is_empty() || self.select.contains(day, part)) && !self.exclude.contains(day, part)
+    }
+}
+
+/// Parses durations like `2s`, `1.5s`, `500ms`, `250us` or `100ns`.
+fn parse_duration(s: &str) -> Result<Duration> {
+    let unit_at = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .ok_or_else(|| anyhow!("missing unit in duration: {s}"))?;
+    let (value, unit) = s.split_at(unit_at);
+    let value: f64 = value.parse().map_err(|_| anyhow!("invalid duration: {s}"))?;
+    let secs = match unit {
+        "s" => value,
+        "ms" => value / 1e3,
+        "us" | "µs" => value / 1e6,
+        "ns" => value / 1e9,
+        _ => bail!("invalid unit in duration: {s} (expected s, ms, us or ns)"),
+    };
+    Duration::try_from_secs_f64(secs).map_err(|_| anyhow!("invalid duration: {s}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations() -> Result<()> {
+        assert_eq!(parse_duration("2s")?, Duration::from_secs(2));
+        assert_eq!(parse_duration("1.5s")?, Duration::from_millis(1500));
+        assert_eq!(parse_duration("250us")?, Duration::from_micros(250));
+        assert!(parse_duration("10").is_err());
+        assert!(parse_duration("10m").is_err());
+        Ok(())
+    }
+}
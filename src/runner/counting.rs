@@ -0,0 +1,134 @@
+use super::Allocs;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// The system allocator, counting the allocations of each thread once [enabled](Counting::enable).
+///
+/// Counting is per thread so that days running concurrently do not see each other's allocations. A reallocation
+/// counts as one allocation of its new size. Only built with the `alloc-stats` feature, as it takes `unsafe` code.
+pub struct Counting;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy)]
+struct Counts {
+    allocs: u64,
+    bytes:  u64,
+    /// Bytes allocated minus bytes freed by this thread, which frees memory allocated by others too.
+    live:   i64,
+    /// Lowest `live` since measuring started.
+    low:    i64,
+    /// Most bytes live at once on top of `low`.
+    peak:   i64,
+}
+
+thread_local! {
+    static COUNTS: Cell<Counts> = const { Cell::new(Counts { allocs: 0, bytes: 0, live: 0, low: 0, peak: 0 }) };
+}
+
+fn count(allocated: usize, freed: usize) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let _ = COUNTS.try_with(|counts| {
+        let mut c = counts.get();
+        if allocated > 0 {
+            c.allocs += 1;
+            c.bytes += allocated as u64;
+        }
+        c.live += allocated as i64 - freed as i64;
+        c.low = c.low.min(c.live);
+        c.peak = c.peak.max(c.live - c.low);
+        counts.set(c);
+    });
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            count(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            count(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        count(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new.is_null() {
+            count(new_size, layout.size());
+        }
+        new
+    }
+}
+
+impl Counting {
+    /// Starts counting allocations, for the whole process.
+    pub fn enable() {
+        ENABLED.store(true, Ordering::Relaxed);
+    }
+
+    pub fn enabled() -> bool {
+        ENABLED.load(Ordering::Relaxed)
+    }
+}
+
+/// Runs `f`, counting the allocations the current thread makes meanwhile.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Allocs) {
+    let before = COUNTS.get();
+    COUNTS.set(Counts { low: before.live, peak: 0, ..before });
+    let result = f();
+    let after = COUNTS.get();
+    let allocs = Allocs {
+        count: after.allocs - before.allocs,
+        bytes: after.bytes - before.bytes,
+        peak:  after.peak as u64,
+    };
+    (result, allocs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counting() {
+        Counting::enable();
+        let (v, allocs) = measure(|| {
+            let scratch = vec![0u8; 4096];
+            drop(scratch);
+            let mut v = Vec::with_capacity(100);
+            v.extend(0u64..100);
+            v
+        });
+        assert_eq!(v.len(), 100);
+        assert_eq!(allocs.count, 2);
+        assert_eq!(allocs.bytes, 4096 + 800);
+        assert_eq!(allocs.peak, 4096);
+
+        // Freeing a previous result and building it again.
+        let (v, allocs) = measure(move || {
+            drop(v);
+            vec![0u64; 100]
+        });
+        assert_eq!((v.len(), allocs.count, allocs.peak), (100, 1, 800));
+
+        let ((), allocs) = measure(|| ());
+        assert_eq!(allocs, Allocs::default());
+    }
+}
@@ -0,0 +1,279 @@
+use super::{alloc, Allocs, Counting, Record, Sampling, Stats};
+use aoc2023::{anyhow, Answer, Harness, Phase, Result, Solution};
+use std::{
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Error of a phase that did not finish in time.
+#[derive(Debug)]
+pub struct Timeout(pub Duration);
+
+impl std::fmt::Display for Timeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "timed out after {:?}", self.0)
+    }
+}
+
+impl std::error::Error for Timeout {}
+
+/// Progress of a day running on its worker thread.
+enum Event {
+    /// A run of a phase started; a phase runs several times when sampled.
+    Started(Phase),
+    Finished(Box<Record>),
+}
+
+/// Harness that times every phase and reports a [`Record`] of each to the thread that [runs](Recorder::run) it.
+#[derive(Clone)]
+pub struct Recorder {
+    day:      String,
+    title:    &'static str,
+    input:    String,
+    sampling: Sampling,
+    expected: [Option<Answer>; 2],
+    /// Where phases are reported as they start and finish, set by [`Recorder::run`].
+    events:   Option<Sender<Event>>,
+}
+
+impl Recorder {
+    pub fn new(
+        day: String,
+        title: &'static str,
+        input: String,
+        sampling: Sampling,
+        expected: [Option<Answer>; 2],
+    ) -> Self {
+        Self { day, title, input, sampling, expected, events: None }
+    }
+
+    fn record(&self, phase: Phase, answer: Result<Option<Answer>>, stats: Stats, allocs: Option<Allocs>) -> Record {
+        let expected = match phase {
+            Phase::Part(part) => self.expected.get(usize::from(part) - 1).cloned().flatten(),
+            Phase::Parse => None,
+        };
+        Record {
+            day: self.day.clone(),
+            title: self.title,
+            input: self.input.clone(),
+            phase,
+            answer,
+            expected,
+            stats,
+            allocs,
+        }
+    }
+
+    /// Runs `parts` of `solution` on `text` on a thread of its own, and returns the records of its phases.
+    ///
+    /// The limit applies to each run of a phase, however many times it is sampled. A run still going after `timeout`
+    /// is recorded as a [`Timeout`] and the rest of the day is given up: its thread cannot be stopped, and is left to
+    /// finish in the background, with a warning since it competes with the days timed after it. Time spent outside
+    /// of the runs, like parsing that is not reported, counts towards the phase that comes next.
+    pub fn run(mut self, solution: Solution, text: String, parts: Vec<u8>, timeout: Option<Duration>) -> Vec<Record> {
+        let (tx, rx) = mpsc::channel();
+        let watchdog = self.clone();
+        self.events = Some(tx);
+        let pending_parts = parts.clone();
+        let worker = thread::Builder::new()
+            .name(format!("{} ({})", self.day, self.input))
+            .spawn(move || solution.run(&text, &parts, &mut self));
+        if let Err(e) = worker {
+            return vec![watchdog.record(Phase::Parse, Err(e.into()), Stats::default(), None)];
+        }
+
+        let mut records: Vec<Record> = vec![];
+        let mut current = None;
+        // When the last run started or finished.
+        let mut since = Instant::now();
+        loop {
+            let event = match timeout {
+                Some(timeout) => rx.recv_timeout(timeout),
+                None => rx.recv().map_err(RecvTimeoutError::from),
+            };
+            match event {
+                Ok(Event::Started(phase)) => {
+                    current = Some(phase);
+                    since = Instant::now();
+                }
+                Ok(Event::Finished(record)) => {
+                    current = None;
+                    since = Instant::now();
+                    records.push(*record);
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    // Between runs, the phase that comes next: parsing until something is reported, as it is not
+                    // when it succeeds without a parse step of its own, then the parts in order.
+                    let done = records.iter().filter(|r| r.phase != Phase::Parse).count();
+                    let pending = match records.is_empty() {
+                        true => Some(Phase::Parse),
+                        false => pending_parts.get(done).map(|&part| Phase::Part(part)),
+                    };
+                    if let (Some(phase), Some(timeout)) = (current.or(pending), timeout) {
+                        let stats = Stats::new(vec![since.elapsed()]);
+                        records.push(watchdog.record(phase, Err(Timeout(timeout).into()), stats, None));
+                    }
+                    eprintln!(
+                        "warning: {} ({}) is still running in the background, later timings may be slower",
+                        watchdog.day, watchdog.input
+                    );
+                    return records;
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    // The worker is done, or panicked in the middle of a phase.
+                    if let Some(phase) = current {
+                        let stats = Stats::new(vec![since.elapsed()]);
+                        records.push(watchdog.record(phase, Err(anyhow!("panicked")), stats, None));
+                    }
+                    return records;
+                }
+            }
+        }
+    }
+}
+
+impl Harness for Recorder {
+    fn phase(&mut self, phase: Phase, f: &mut dyn FnMut() -> Result<Option<Answer>>) -> bool {
+        let events = self.events.clone();
+        let started = || {
+            if let Some(events) = &events {
+                let _ = events.send(Event::Started(phase));
+            }
+        };
+        // Every run allocates the same, so the last one speaks for all of them.
+        let (answer, stats, allocs) = match Counting::enabled() {
+            true => {
+                let mut allocs = None;
+                let (answer, stats) = self.sampling.run(started, || {
+                    let (answer, counted) = alloc::measure(&mut *f);
+                    allocs = Some(counted);
+                    answer
+                });
+                (answer, stats, allocs)
+            }
+            false => {
+                let (answer, stats) = self.sampling.run(started, f);
+                (answer, stats, None)
+            }
+        };
+        let ok = answer.is_ok();
+        let record = self.record(phase, answer, stats, allocs);
+        if let Some(events) = &self.events {
+            let _ = events.send(Event::Finished(Box::new(record)));
+        }
+        ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc2023::{Day, StrDay};
+
+    #[test]
+    fn timeout() {
+        const SOLUTION: Solution = Solution {
+            day:      &StrDay {
+                day:   1,
+                title: "Slow",
+                part1: |input| Ok(input.len()),
+                part2: |_| -> Result<usize> {
+                    thread::sleep(Duration::from_secs(10));
+                    Ok(0)
+                },
+            },
+            source:   "src/day01/mod.rs",
+            inputs:   &[],
+            generate: None,
+        };
+        let recorder = Recorder::new("day01".into(), "Slow", "input".into(), Sampling::default(), [
+            None, None,
+        ]);
+        let records = recorder.run(SOLUTION, "abc".into(), vec![1, 2, 1], Some(Duration::from_millis(50)));
+
+        let summary = records
+            .iter()
+            .map(|r| (r.phase, r.answer.as_ref().map_err(|e| e.to_string()).cloned()))
+            .collect::<Vec<_>>();
+        assert_eq!(summary, [
+            (Phase::Part(1), Ok(Some(Answer::Int(3)))),
+            (Phase::Part(2), Err("timed out after 50ms".to_owned())),
+        ]);
+        assert!(records[1].stats.median >= Duration::from_millis(50));
+
+        // The limit applies to each run: many runs may add up to more.
+        const STEADY: Solution = Solution {
+            day: &StrDay {
+                day:   1,
+                title: "Steady",
+                part1: |input| {
+                    thread::sleep(Duration::from_millis(10));
+                    Ok(input.len())
+                },
+                part2: |_| Ok(0),
+            },
+            ..SOLUTION
+        };
+        let sampling = Sampling { repeat: 10, ..Default::default() };
+        let recorder = Recorder::new("day01".into(), "Steady", "input".into(), sampling, [None, None]);
+        let records = recorder.run(STEADY, "abc".into(), vec![1], Some(Duration::from_millis(50)));
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].answer.as_ref().ok(), Some(&Some(Answer::Int(3))));
+        assert_eq!(records[0].stats.samples, 10);
+    }
+
+    /// Parses slowly, without a parse step of its own: parsing is not a phase that starts.
+    struct SlowParse;
+
+    impl Day for SlowParse {
+        type Input<'a> = &'a str;
+        type Output1 = usize;
+        type Output2 = usize;
+
+        fn day(&self) -> u8 {
+            1
+        }
+
+        fn title(&self) -> &'static str {
+            "Slow parse"
+        }
+
+        fn has_parse_step(&self) -> bool {
+            false
+        }
+
+        fn parse<'a>(&self, input: &'a str) -> Result<&'a str> {
+            thread::sleep(Duration::from_secs(10));
+            Ok(input)
+        }
+
+        fn part1(&self, input: &&str) -> Result<usize> {
+            Ok(input.len())
+        }
+
+        fn part2(&self, input: &&str) -> Result<usize> {
+            Ok(input.len())
+        }
+    }
+
+    #[test]
+    fn timeout_between_phases() {
+        const SOLUTION: Solution = Solution {
+            day:      &SlowParse,
+            source:   "src/day01/mod.rs",
+            inputs:   &[],
+            generate: None,
+        };
+        let recorder = Recorder::new("day01".into(), "Slow parse", "input".into(), Sampling::default(), [
+            None, None,
+        ]);
+        let records = recorder.run(SOLUTION, "abc".into(), vec![1, 2], Some(Duration::from_millis(50)));
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].phase, Phase::Parse);
+        assert_eq!(
+            records[0].answer.as_ref().map_err(|e| e.to_string()).err().as_deref(),
+            Some("timed out after 50ms")
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use aoc2023::Result;
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+/// Runs `work` on every item using up to `jobs` scoped threads, and hands the results to `report` in the
+/// original order of the items as soon as they are available. Workers stop taking items once `report` fails.
+pub fn run_ordered<T, R, W, F>(items: Vec<T>, jobs: usize, work: W, mut report: F) -> Result<()>
+where
+    T: Send + Sync,
+    R: Send,
+    W: Fn(&T) -> R + Sync,
+    F: FnMut(R) -> Result<()>,
+{
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|s| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            let (tx, next, stop, items, work) = (tx.clone(), &next, &stop, &items, &work);
+            s.spawn(move || loop {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else { break };
+                if tx.send((i, work(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        let mut pending = BTreeMap::new();
+        let mut expected = 0;
+        for (i, result) in rx.iter() {
+            pending.insert(i, result);
+            while let Some(result) = pending.remove(&expected) {
+                if let Err(e) = report(result) {
+                    stop.store(true, Ordering::Relaxed);
+                    return Err(e);
+                }
+                expected += 1;
+            }
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ordered() -> Result<()> {
+        for jobs in [1, 3, 16] {
+            let mut seen = Vec::new();
+            run_ordered(
+                (0..10u64).collect(),
+                jobs,
+                |&i| {
+                    thread::sleep(std::time::Duration::from_millis(10 - i));
+                    i * 2
+                },
+                |r| {
+                    seen.push(r);
+                    Ok(())
+                },
+            )?;
+            assert_eq!(seen, (0..10).map(|i| i * 2).collect::<Vec<_>>());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn stops_on_error() {
+        let done = AtomicUsize::new(0);
+        let res = run_ordered(
+            (0..100u64).collect(),
+            2,
+            |&i| {
+                thread::sleep(std::time::Duration::from_millis(1));
+                done.fetch_add(1, Ordering::Relaxed);
+                i
+            },
+            |i| match i {
+                0 => Err(aoc2023::anyhow!("broken pipe")),
+                _ => Ok(()),
+            },
+        );
+        assert!(res.is_err());
+        // Workers stop soon after the first result fails, rather than running through all the items.
+        assert!(done.load(Ordering::Relaxed) < 50);
+    }
+}
@@ -0,0 +1,19 @@
+mod alloc;
+mod args;
+#[cfg(feature = "alloc-stats")]
+mod counting;
+mod harness;
+mod jobs;
+mod report;
+mod scale;
+mod select;
+mod stats;
+
+pub use alloc::{Allocs, Counting};
+pub use args::Options;
+pub use harness::{Recorder, Timeout};
+pub use jobs::run_ordered;
+pub use report::{Format, Record, Reporter};
+pub use scale::{sizes, Growth};
+pub use select::{parse_day, Selection};
+pub use stats::{Sampling, Stats};
@@ -0,0 +1,440 @@
+use super::{alloc::format_bytes, Allocs, Stats, Timeout};
+use aoc2023::{bail, Answer, Error, FromStr, Itertools, Phase, Result};
+use std::{io::Write, time::Duration};
+
+/// Outcome of running one phase of one day. The parse phase has no answer.
+pub struct Record {
+    pub day:      String,
+    pub title:    &'static str,
+    /// Name of the input, see [`aoc2023::Input::name`], or the path given with `--input`.
+    pub input:    String,
+    pub phase:    Phase,
+    pub answer:   Result<Option<Answer>>,
+    pub expected: Option<Answer>,
+    pub stats:    Stats,
+    /// Allocations of one run, when counted with `--allocs`.
+    pub allocs:   Option<Allocs>,
+}
+
+/// Result of comparing an answer against the expected one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    Unknown,
+    /// The part failed with an error or panicked, whether or not an answer is expected.
+    Error,
+    /// The part did not finish within the time limit.
+    Timeout,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Pass => "PASS",
+            Self::Fail => "FAIL",
+            Self::Unknown => "UNKNOWN",
+            Self::Error => "ERROR",
+            Self::Timeout => "TIMEOUT",
+        })
+    }
+}
+
+impl Record {
+    pub fn status(&self) -> Status {
+        match (&self.answer, &self.expected) {
+            (Err(e), _) if e.is::<Timeout>() => Status::Timeout,
+            (Err(_), _) => Status::Error,
+            (_, None) => Status::Unknown,
+            (Ok(Some(answer)), Some(expected)) if answer == expected => Status::Pass,
+            _ => Status::Fail,
+        }
+    }
+
+    /// Whether this part errored, timed out or gave an answer other than the expected one.
+    pub fn failed(&self) -> bool {
+        matches!(self.status(), Status::Fail | Status::Error | Status::Timeout)
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => bail!("unknown format: {s} (expected text, json or csv)"),
+        }
+    }
+}
+
+enum Field {
+    Null,
+    Str(String),
+    Num(u128),
+    Signed(i64),
+}
+
+impl From<&Answer> for Field {
+    fn from(answer: &Answer) -> Self {
+        match answer {
+            Answer::Int(n) => Self::Num((*n).into()),
+            Answer::Signed(n) => Self::Signed(*n),
+            _ => Self::Str(answer.to_string()),
+        }
+    }
+}
+
+/// Columns of the machine-readable formats, in order.
+const COLUMNS: [&str; 17] = [
+    "day",
+    "input",
+    "phase",
+    "part",
+    "answer",
+    "error",
+    "expected",
+    "status",
+    "duration_ns",
+    "min_ns",
+    "mean_ns",
+    "p95_ns",
+    "stddev_ns",
+    "samples",
+    "allocs",
+    "alloc_bytes",
+    "peak_bytes",
+];
+
+impl Record {
+    fn fields(&self) -> [Field; COLUMNS.len()] {
+        let (answer, error) = match &self.answer {
+            Ok(answer) => (answer.as_ref().map_or(Field::Null, Field::from), Field::Null),
+            Err(e) => (Field::Null, Field::Str(format!("{:#}", e))),
+        };
+        let (phase, part, status) = match self.phase {
+            Phase::Parse => ("parse".to_owned(), Field::Null, Field::Null),
+            Phase::Part(part) => (
+                format!("part{part}"),
+                Field::Num(part.into()),
+                Field::Str(self.status().to_string()),
+            ),
+        };
+        let Stats { samples, min, median, mean, p95, stddev } = self.stats;
+        let allocs = self.allocs;
+        [
+            Field::Str(self.day.clone()),
+            Field::Str(self.input.clone()),
+            Field::Str(phase),
+            part,
+            answer,
+            error,
+            self.expected.as_ref().map_or(Field::Null, Field::from),
+            status,
+            Field::Num(median.as_nanos()),
+            Field::Num(min.as_nanos()),
+            Field::Num(mean.as_nanos()),
+            Field::Num(p95.as_nanos()),
+            Field::Num(stddev.as_nanos()),
+            Field::Num(samples as u128),
+            allocs.map_or(Field::Null, |a| Field::Num(a.count.into())),
+            allocs.map_or(Field::Null, |a| Field::Num(a.bytes.into())),
+            allocs.map_or(Field::Null, |a| Field::Num(a.peak.into())),
+        ]
+    }
+}
+
+/// Writes records to `out` in the chosen format as they are produced.
+pub struct Reporter<W: Write> {
+    out:    W,
+    format: Format,
+    count:  usize,
+    /// Day and input of the last record, whose header has been written already.
+    day:    Option<(String, String)>,
+}
+
+impl<W: Write> Reporter<W> {
+    pub fn new(out: W, format: Format) -> Self {
+        Self { out, format, count: 0, day: None }
+    }
+
+    pub fn record(&mut self, record: &Record) -> Result<()> {
+        match self.format {
+            Format::Text => self.text(record)?,
+            Format::Json => {
+                let fields = COLUMNS.iter().zip(record.fields()).map(|(name, field)| {
+                    let value = match field {
+                        Field::Null => "null".to_owned(),
+                        Field::Str(s) => json_string(&s),
+                        Field::Num(n) => n.to_string(),
+                        Field::Signed(n) => n.to_string(),
+                    };
+                    format!("\"{name}\":{value}")
+                });
+                let sep = if self.count == 0 { "[\n  " } else { ",\n  " };
+                write!(self.out, "{}{{{}}}", sep, fields.format(","))?;
+            }
+            Format::Csv => {
+                if self.count == 0 {
+                    writeln!(self.out, "{}", COLUMNS.join(","))?;
+                }
+                let fields = record.fields().into_iter().map(|field| match field {
+                    Field::Null => String::new(),
+                    Field::Str(s) => csv_field(&s),
+                    Field::Num(n) => n.to_string(),
+                    Field::Signed(n) => n.to_string(),
+                });
+                writeln!(self.out, "{}", fields.format(","))?;
+            }
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    fn text(&mut self, record: &Record) -> Result<()> {
+        let Record { day, title, input, phase, answer, expected, stats, allocs } = record;
+        if self.day.as_ref().is_none_or(|last| (&last.0, &last.1) != (day, input)) {
+            if self.day.is_some() {
+                writeln!(self.out)?;
+            }
+            write!(self.out, "[{}]", day)?;
+            if !title.is_empty() {
+                write!(self.out, " {}", title)?;
+            }
+            // The `input` file of the day goes without saying.
+            match input.as_str() {
+                "input" => writeln!(self.out)?,
+                _ => writeln!(self.out, " ({})", input)?,
+            }
+            self.day = Some((day.clone(), input.clone()));
+        }
+        // Multi-line answers go below the line, indented.
+        let mut block = None;
+        match answer {
+            Ok(Some(Answer::Block(text))) => {
+                write!(self.out, "{:7} {:10}", format!("{phase}:"), "")?;
+                block = Some(text);
+            }
+            Ok(Some(answer)) => write!(self.out, "{:7} {:10}", format!("{phase}:"), answer)?,
+            Ok(None) => write!(self.out, "{:7} {:10}", format!("{phase}:"), "")?,
+            Err(e) => write!(self.out, "{}: error: {:#}", phase, e)?,
+        }
+        match (*phase, record.status(), expected) {
+            (Phase::Parse, ..) => {}
+            (_, Status::Fail, Some(expected)) => write!(self.out, " [FAIL: expected {}]", expected)?,
+            (_, status, _) => write!(self.out, " [{}]", status)?,
+        }
+        write!(self.out, " - time: {:?}", stats.median)?;
+        if stats.samples > 1 {
+            let Stats { samples, min, mean, p95, stddev, .. } = stats;
+            write!(
+                self.out,
+                " (min: {min:?}, mean: {mean:?}, p95: {p95:?}, σ: {stddev:?}, n: {samples})"
+            )?;
+        }
+        if let Some(Allocs { count, bytes, peak }) = allocs {
+            write!(
+                self.out,
+                " - allocs: {count} ({}, peak: {})",
+                format_bytes(*bytes),
+                format_bytes(*peak)
+            )?;
+        }
+        writeln!(self.out)?;
+        for line in block.into_iter().flat_map(|text| text.trim_matches('\n').lines()) {
+            writeln!(self.out, "        {}", line)?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self, total: Duration) -> Result<()> {
+        match self.format {
+            Format::Text => {
+                if self.day.is_some() {
+                    writeln!(self.out)?;
+                }
+                writeln!(self.out, "Total time: {:?}", total)?
+            }
+            Format::Json if self.count == 0 => writeln!(self.out, "[]")?,
+            Format::Json => writeln!(self.out, "\n]")?,
+            Format::Csv if self.count == 0 => writeln!(self.out, "{}", COLUMNS.join(","))?,
+            Format::Csv => {}
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    match s.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", s.replace('"', "\"\"")),
+        false => s.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc2023::{anyhow, IntoAnswer};
+
+    fn render(format: Format, records: Vec<Record>) -> Result<String> {
+        let mut out = Vec::new();
+        let mut reporter = Reporter::new(&mut out, format);
+        for record in &records {
+            reporter.record(record)?;
+        }
+        reporter.finish(records.iter().map(|r| r.stats.median).sum())?;
+        Ok(String::from_utf8(out)?)
+    }
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day:      "day01".into(),
+                title:    "Test",
+                input:    "input".into(),
+                phase:    Phase::Parse,
+                answer:   Ok(None),
+                expected: None,
+                stats:    Stats::new(vec![Duration::from_nanos(500)]),
+                allocs:   None,
+            },
+            Record {
+                day:      "day01".into(),
+                title:    "Test",
+                input:    "input".into(),
+                phase:    Phase::Part(1),
+                answer:   Ok(Some(Answer::Int(42))),
+                expected: Some(Answer::Int(42)),
+                stats:    Stats::new(vec![Duration::from_nanos(1500)]),
+                allocs:   Some(Allocs { count: 3, bytes: 1536, peak: 1024 }),
+            },
+            Record {
+                day:      "day01".into(),
+                title:    "Test",
+                input:    "input".into(),
+                phase:    Phase::Part(2),
+                answer:   Err(anyhow!("bad \"input\", line 1")),
+                expected: None,
+                stats:    Stats::new(vec![Duration::from_nanos(20), Duration::from_nanos(30)]),
+                allocs:   None,
+            },
+        ]
+    }
+
+    #[test]
+    fn text() -> Result<()> {
+        assert_eq!(render(Format::Text, records())?, indoc::indoc! {r#"
+            [day01] Test
+            parse:             - time: 500ns
+            part 1: 42         [PASS] - time: 1.5µs - allocs: 3 (1.5 KiB, peak: 1.0 KiB)
+            part 2: error: bad "input", line 1 [ERROR] - time: 25ns (min: 20ns, mean: 25ns, p95: 30ns, σ: 7ns, n: 2)
+
+            Total time: 2.025µs
+        "#});
+
+        let block = Record {
+            day:      "day02".into(),
+            title:    "",
+            input:    "alice".into(),
+            phase:    Phase::Part(1),
+            answer:   Ok(Some("#.\n.#\n".into_answer())),
+            expected: Some("\n#.\n.#".into_answer()),
+            stats:    Stats::new(vec![Duration::from_nanos(10)]),
+            allocs:   None,
+        };
+        assert_eq!(render(Format::Text, vec![block])?, indoc::indoc! {"
+            [day02] (alice)
+            part 1:            [PASS] - time: 10ns
+                    #.
+                    .#
+
+            Total time: 10ns
+        "});
+        Ok(())
+    }
+
+    #[test]
+    fn status() {
+        let record = |answer: Result<Option<Answer>>, expected: Option<Answer>| Record {
+            day: "day01".into(),
+            title: "",
+            input: "input".into(),
+            phase: Phase::Part(1),
+            answer,
+            expected,
+            stats: Stats::default(),
+            allocs: None,
+        };
+        let one = || Some(Answer::Int(1));
+        assert_eq!(record(Ok(one()), one()).status(), Status::Pass);
+        assert_eq!(record(Ok(one()), Some(Answer::Signed(1))).status(), Status::Pass);
+        assert_eq!(record(Ok(one()), Some(Answer::Int(2))).status(), Status::Fail);
+        assert_eq!(record(Ok(one()), Some(Answer::Text("1".into()))).status(), Status::Pass);
+        assert_eq!(
+            record(Ok(one()), Some(Answer::Text("01".into()))).status(),
+            Status::Fail
+        );
+        assert_eq!(record(Err(anyhow!("oops")), one()).status(), Status::Error);
+        assert_eq!(record(Err(anyhow!("oops")), None).status(), Status::Error);
+        assert_eq!(record(Ok(one()), None).status(), Status::Unknown);
+        let timeout = || Err(Timeout(Duration::from_secs(1)).into());
+        assert_eq!(record(timeout(), one()).status(), Status::Timeout);
+        assert_eq!(record(timeout(), None).status(), Status::Timeout);
+        assert!(record(timeout(), None).failed());
+        assert!(record(Err(anyhow!("oops")), None).failed());
+        assert!(!record(Ok(one()), None).failed());
+    }
+
+    #[test]
+    fn json() -> Result<()> {
+        assert_eq!(render(Format::Json, records())?, indoc::indoc! {r#"
+            [
+              {"day":"day01","input":"input","phase":"parse","part":null,"answer":null,"error":null,"expected":null,"status":null,"duration_ns":500,"min_ns":500,"mean_ns":500,"p95_ns":500,"stddev_ns":0,"samples":1,"allocs":null,"alloc_bytes":null,"peak_bytes":null},
+              {"day":"day01","input":"input","phase":"part1","part":1,"answer":42,"error":null,"expected":42,"status":"PASS","duration_ns":1500,"min_ns":1500,"mean_ns":1500,"p95_ns":1500,"stddev_ns":0,"samples":1,"allocs":3,"alloc_bytes":1536,"peak_bytes":1024},
+              {"day":"day01","input":"input","phase":"part2","part":2,"answer":null,"error":"bad \"input\", line 1","expected":null,"status":"ERROR","duration_ns":25,"min_ns":20,"mean_ns":25,"p95_ns":30,"stddev_ns":7,"samples":2,"allocs":null,"alloc_bytes":null,"peak_bytes":null}
+            ]
+        "#});
+        assert_eq!(render(Format::Json, vec![])?, "[]\n");
+        Ok(())
+    }
+
+    #[test]
+    fn csv() -> Result<()> {
+        assert_eq!(render(Format::Csv, records())?, indoc::indoc! {r#"
+            day,input,phase,part,answer,error,expected,status,duration_ns,min_ns,mean_ns,p95_ns,stddev_ns,samples,allocs,alloc_bytes,peak_bytes
+            day01,input,parse,,,,,,500,500,500,500,0,1,,,
+            day01,input,part1,1,42,,42,PASS,1500,1500,1500,1500,0,1,3,1536,1024
+            day01,input,part2,2,,"bad ""input"", line 1",,ERROR,25,20,25,30,7,2,,,
+        "#});
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use super::Record;
+use aoc2023::{Itertools, Phase, Result};
+use std::{io::Write, time::Duration};
+
+/// Number of input sizes each part is timed on.
+const STEPS: u32 = 6;
+
+/// Sizes of the inputs generated to see how the parts scale: halvings of `max`, then `max` itself.
+pub fn sizes(max: usize) -> Vec<usize> {
+    (0..STEPS)
+        .rev()
+        .map(|k| max >> k)
+        .filter(|&size| size > 0)
+        .dedup()
+        .collect()
+}
+
+/// Fits `time = c * size^k` to `points` by least squares on their logarithms, and returns `k`. Needs two sizes at
+/// least.
+pub fn growth_exponent(points: &[(usize, Duration)]) -> Option<f64> {
+    let logs = points
+        .iter()
+        .filter(|(size, time)| *size > 0 && !time.is_zero())
+        .map(|&(size, time)| ((size as f64).ln(), time.as_secs_f64().ln()))
+        .collect_vec();
+    let n = logs.len() as f64;
+    let (mean_x, mean_y) = (
+        logs.iter().map(|p| p.0).sum::<f64>() / n,
+        logs.iter().map(|p| p.1).sum::<f64>() / n,
+    );
+    let sxx = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum::<f64>();
+    let sxy = logs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum::<f64>();
+    (logs.len() >= 2 && sxx > 0.0).then(|| sxy / sxx)
+}
+
+/// Median times of a phase of a day, by input size.
+struct Series {
+    day:    String,
+    phase:  Phase,
+    points: Vec<(usize, Duration)>,
+}
+
+/// Timings of each phase of each day over growing input sizes.
+#[derive(Default)]
+pub struct Growth {
+    series: Vec<Series>,
+}
+
+impl Growth {
+    /// Adds the timing of `record`, made on an input of `size`. Failed phases are left out.
+    pub fn add(&mut self, size: usize, record: &Record) {
+        if record.answer.is_err() {
+            return;
+        }
+        let point = (size, record.stats.median);
+        match self
+            .series
+            .iter_mut()
+            .find(|series| series.day == record.day && series.phase == record.phase)
+        {
+            Some(series) => series.points.push(point),
+            None => self.series.push(Series {
+                day:    record.day.clone(),
+                phase:  record.phase,
+                points: vec![point],
+            }),
+        }
+    }
+
+    pub fn write(&self, mut out: impl Write) -> Result<()> {
+        writeln!(out, "Growth of the time with the input size (time ~ size^k):")?;
+        for Series { day, phase, points } in &self.series {
+            match growth_exponent(points) {
+                Some(k) => writeln!(out, "[{day}] {phase}: k = {k:.2}")?,
+                None => writeln!(out, "[{day}] {phase}: not enough timings")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponent() {
+        let points = |f: fn(f64) -> f64| {
+            sizes(1 << 12)
+                .into_iter()
+                .map(|n| (n, Duration::from_secs_f64(f(n as f64) * 1e-9)))
+                .collect_vec()
+        };
+        let k = |f| growth_exponent(&points(f)).unwrap_or(f64::NAN);
+        assert!((k(|n| 3.0 * n) - 1.0).abs() < 1e-3);
+        assert!((k(|n| n * n) - 2.0).abs() < 1e-3);
+        assert!((k(|n| n * n.ln()) - 1.15).abs() < 0.05);
+        assert!(growth_exponent(&[(10, Duration::from_secs(1))]).is_none());
+    }
+
+    #[test]
+    fn steps() {
+        assert_eq!(sizes(1000), [31, 62, 125, 250, 500, 1000]);
+        assert_eq!(sizes(4), [1, 2, 4]);
+    }
+}
@@ -0,0 +1,121 @@
+use aoc2023::{anyhow, bail, ensure, Error, FromStr, Itertools, Result};
+use std::ops::RangeInclusive;
+
+/// One item of a selection: a day or range of days, optionally restricted to a single part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Item {
+    days: RangeInclusive<u32>,
+    part: Option<u8>,
+}
+
+/// A set of days and parts given on the command line, e.g. `5`, `day05`, `3-7`, `1,4,9` or `5:2`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection {
+    items: Vec<Item>,
+}
+
+/// Parses a day number given as `5`, `05` or `day05`.
+pub fn parse_day(s: &str) -> Result<u32> {
+    let digits = s.strip_prefix("day").unwrap_or(s);
+    ensure!(
+        !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()),
+        "invalid day: {s}"
+    );
+    Ok(digits.parse()?)
+}
+
+impl FromStr for Selection {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let items = s
+            .split(',')
+            .map(|item| {
+                let (days, part) = match item.split_once(':') {
+                    Some((days, part)) => match part {
+                        "1" | "2" => (days, Some(part.as_bytes()[0] - b'0')),
+                        _ => bail!("invalid part in {item}: expected 1 or 2"),
+                    },
+                    None => (item, None),
+                };
+                let days = match days.split_once('-') {
+                    Some((start, end)) => parse_day(start)?..=parse_day(end)?,
+                    None => parse_day(days).map(|day| day..=day)?,
+                };
+                ensure!(!days.is_empty(), "invalid range: {item}");
+                Ok(Item { days, part })
+            })
+            .try_collect()?;
+        Ok(Self { items })
+    }
+}
+
+impl Selection {
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn extend(&mut self, other: Self) {
+        self.items.extend(other.items)
+    }
+
+    pub fn contains(&self, day: u32, part: u8) -> bool {
+        self.items
+            .iter()
+            .any(|item| item.days.contains(&day) && item.part.is_none_or(|p| p == part))
+    }
+
+    /// Fails if some item matches none of the `available` days.
+    pub fn check(&self, available: &[u32]) -> Result<()> {
+        match self
+            .items
+            .iter()
+            .find(|item| !available.iter().any(|day| item.days.contains(day)))
+        {
+            Some(item) => Err(anyhow!(
+                "no such day: {}; available days: {}",
+                match item.days.clone().into_inner() {
+                    (start, end) if start == end => start.to_string(),
+                    (start, end) => format!("{start}-{end}"),
+                },
+                available.iter().join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() -> Result<()> {
+        for s in ["5", "05", "day05"] {
+            assert_eq!(s.parse::<Selection>()?, Selection {
+                items: vec![Item { days: 5..=5, part: None }],
+            });
+        }
+        let sel: Selection = "3-7,day09:2".parse()?;
+        assert!(sel.contains(3, 1) && sel.contains(7, 2) && sel.contains(9, 2));
+        assert!(!sel.contains(8, 1) && !sel.contains(9, 1));
+
+        for s in ["", "x", "5:3", "7-3", "day", "1,,2", "-5"] {
+            assert!(s.parse::<Selection>().is_err(), "{s}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn check() -> Result<()> {
+        let available = [1, 2, 3];
+        assert!("1-5".parse::<Selection>()?.check(&available).is_ok());
+        let err = "2,4"
+            .parse::<Selection>()?
+            .check(&available)
+            .err()
+            .map(|e| e.to_string());
+        assert_eq!(err.as_deref(), Some("no such day: 4; available days: 1, 2, 3"));
+        Ok(())
+    }
+}
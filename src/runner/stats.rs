@@ -0,0 +1,160 @@
+use std::time::{Duration, Instant};
+
+/// How many times each part is run when measuring it.
+#[derive(Clone, Copy)]
+pub struct Sampling {
+    /// Minimum number of timed runs.
+    pub repeat:   usize,
+    /// Keep running until the timed runs add up to at least this long.
+    pub min_time: Duration,
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Self { repeat: 1, min_time: Duration::ZERO }
+    }
+}
+
+impl Sampling {
+    /// A single run is timed as is; anything more gets an untimed warm-up run first.
+    fn warm_up(&self) -> bool {
+        self.repeat > 1 || !self.min_time.is_zero()
+    }
+
+    /// Runs `f` according to the sampling settings and returns the last result along with the timing statistics.
+    /// Sampling stops at the first run that fails. `before` is called ahead of every run, outside of its timing.
+    ///
+    /// At most [`MAX_SAMPLES`] samples are kept: past that, each sample becomes the mean time of a batch of runs,
+    /// twice as large every time the limit is reached again, so that long runs of fast parts use bounded memory.
+    pub fn run<T, E>(&self, mut before: impl FnMut(), mut f: impl FnMut() -> Result<T, E>) -> (Result<T, E>, Stats) {
+        if self.warm_up() {
+            before();
+            let now = Instant::now();
+            if let Err(e) = f() {
+                return (Err(e), Stats::new(vec![now.elapsed()]));
+            }
+        }
+
+        let mut samples = Vec::with_capacity(self.repeat.min(MAX_SAMPLES));
+        let (mut batch, mut batched, mut batch_time) = (1, 0, Duration::ZERO);
+        let (mut runs, mut elapsed) = (0, Duration::ZERO);
+        loop {
+            before();
+            let now = Instant::now();
+            let r = f();
+            let t = now.elapsed();
+            runs += 1;
+            elapsed += t;
+            batched += 1;
+            batch_time += t;
+            let done = r.is_err() || (runs >= self.repeat && elapsed >= self.min_time);
+            if batched == batch || done {
+                samples.push(batch_time / batched);
+                (batched, batch_time) = (0, Duration::ZERO);
+                if samples.len() == MAX_SAMPLES {
+                    samples = samples
+                        .chunks(2)
+                        .map(|pair| pair.iter().sum::<Duration>() / 2)
+                        .collect();
+                    batch *= 2;
+                }
+            }
+            if done {
+                return (r, Stats { samples: runs, ..Stats::new(samples) });
+            }
+        }
+    }
+}
+
+/// Most samples kept by [`Sampling::run`].
+const MAX_SAMPLES: usize = 1 << 12;
+
+/// Summary of repeated timings of the same piece of work.
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct Stats {
+    /// Number of timed runs.
+    pub samples: usize,
+    pub min:     Duration,
+    pub median:  Duration,
+    pub mean:    Duration,
+    pub p95:     Duration,
+    pub stddev:  Duration,
+}
+
+impl Stats {
+    pub fn new(mut samples: Vec<Duration>) -> Self {
+        let n = samples.len();
+        if n == 0 {
+            return Self::default();
+        }
+        samples.sort_unstable();
+
+        let median = match n % 2 {
+            0 => (samples[n / 2 - 1] + samples[n / 2]) / 2,
+            _ => samples[n / 2],
+        };
+        let p95 = samples[(n * 95).div_ceil(100) - 1];
+        let mean = samples.iter().sum::<Duration>() / n as u32;
+        let stddev = match n {
+            1 => Duration::ZERO,
+            _ => {
+                let mean = mean.as_secs_f64();
+                let var = samples.iter().map(|t| (t.as_secs_f64() - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+                Duration::from_secs_f64(var.sqrt())
+            }
+        };
+
+        Self { samples: n, min: samples[0], median, mean, p95, stddev }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let stats = Stats::new((1..=20).rev().map(Duration::from_millis).collect());
+        assert_eq!(stats.samples, 20);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(10500));
+        assert_eq!(stats.mean, Duration::from_micros(10500));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+        assert_eq!(stats.stddev.as_micros(), 5916);
+
+        let stats = Stats::new(vec![Duration::from_millis(3)]);
+        assert_eq!(
+            (stats.median, stats.p95, stats.stddev),
+            (stats.min, stats.min, Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn sampling() {
+        let mut runs = 0;
+        let (r, stats) = Sampling { repeat: 5, ..Default::default() }.run(
+            || {},
+            || -> Result<_, ()> {
+                runs += 1;
+                Ok(runs)
+            },
+        );
+        assert_eq!((r, stats.samples), (Ok(6), 5));
+
+        let (r, stats) = Sampling { repeat: 5, ..Default::default() }.run(|| {}, || Err::<(), _>("boom"));
+        assert_eq!((r, stats.samples), (Err("boom"), 1));
+
+        // Past `MAX_SAMPLES`, runs are timed in batches, and still all counted.
+        let repeat = 3 * MAX_SAMPLES + 1;
+        let mut runs = 0;
+        let (r, stats) = Sampling { repeat, ..Default::default() }.run(
+            || {},
+            || -> Result<_, ()> {
+                runs += 1;
+                Ok(runs)
+            },
+        );
+        assert_eq!((r, stats.samples), (Ok(repeat + 1), repeat));
+        assert!(stats.min <= stats.median && stats.median <= stats.p95);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{fetch::Fetcher, runner::parse_day};
+use anyhow::Context;
+use aoc2023::{bail, ensure, Result};
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+const TEMPLATE: &str = include_str!("../templates/mod.rs");
+
+/// Options of the `new` subcommand.
+#[derive(Debug, Default)]
+pub struct NewOptions {
+    pub day:   u8,
+    pub title: String,
+    /// Import the puzzle input from this path (`-` for stdin) instead of leaving it empty.
+    pub input: Option<String>,
+    /// Fetch the puzzle input, see [`crate::fetch::from_env`].
+    pub fetch: bool,
+}
+
+impl NewOptions {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut opts = Self::default();
+        let mut day = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_owned())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || match inline.clone().or_else(|| args.next()) {
+                Some(value) => Ok(value),
+                None => bail!("missing value for {name}"),
+            };
+            match name {
+                "-t" | "--title" => opts.title = value()?,
+                "-i" | "--input" => opts.input = Some(value()?),
+                "--fetch" => opts.fetch = true,
+                _ if name.starts_with('-') => bail!("unknown option: {name}"),
+                _ if day.is_some() => bail!("unexpected argument: {arg}"),
+                _ => day = Some(parse_day(&arg)?),
+            }
+        }
+        match day {
+            Some(day @ 1..=25) => opts.day = day as u8,
+            Some(day) => bail!("invalid day: {day} (expected 1 to 25)"),
+            None => bail!("missing day, e.g. `new 11`"),
+        }
+        ensure!(
+            opts.input.is_none() || !opts.fetch,
+            "--input and --fetch cannot be used together"
+        );
+        Ok(opts)
+    }
+}
+
+/// Creates `src/dayNN` in the crate at `root`, which `build.rs` registers and compiles with the `all` feature.
+///
+/// The input is imported from `opts.input`, fetched with `fetcher` if given, or left empty. Nothing is left behind
+/// if any step fails: the new directory is removed.
+pub fn new_day(root: &Path, opts: &NewOptions, fetcher: Option<&dyn Fetcher>) -> Result<PathBuf> {
+    let module = format!("day{:02}", opts.day);
+    let dir = root.join("src").join(&module);
+    // Checked early so as not to fetch an input in vain; `create` checks again, without racing other processes.
+    ensure!(!dir.exists(), "{} already exists", dir.display());
+
+    let input = match (&opts.input, fetcher) {
+        (Some(path), _) => crate::read_input(path)?,
+        (None, Some(fetcher)) => fetcher.fetch(opts.day)?,
+        (None, None) => String::new(),
+    };
+
+    create(&dir, &[("mod.rs", &render(opts)), ("input", &input)])?;
+    Ok(dir)
+}
+
+/// The line to add to the features of the `Cargo.toml` at `root` to build `day` on its own, unless it is there
+/// already. `new` leaves `Cargo.toml` alone, as the `all` feature builds every day anyway.
+pub fn missing_feature(root: &Path, day: u8) -> Result<Option<String>> {
+    let path = root.join("Cargo.toml");
+    let manifest = fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    let feature = format!("day{day:02}");
+    let listed = manifest
+        .lines()
+        .any(|line| line.split('=').next().is_some_and(|name| name.trim() == feature));
+    Ok((!listed).then(|| format!("{feature} = []")))
+}
+
+/// Creates `dir`, which must not exist yet, with `files`, given by name and contents, or removes it again if any of
+/// them cannot be written.
+fn create(dir: &Path, files: &[(&str, &str)]) -> Result<()> {
+    match fs::create_dir(dir) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => bail!("{} already exists", dir.display()),
+        Err(e) => return Err(e).with_context(|| format!("failed to create {}", dir.display())),
+    }
+    let write_all = || -> Result<()> {
+        for (name, contents) in files {
+            let path = dir.join(name);
+            fs::write(&path, contents).with_context(|| format!("failed to write {}", path.display()))?;
+        }
+        Ok(())
+    };
+    write_all().inspect_err(|_| {
+        let _ = fs::remove_dir_all(dir);
+    })
+}
+
+/// Fills in the template for a new day.
+fn render(opts: &NewOptions) -> String {
+    TEMPLATE
+        .replace("{{day}}", &opts.day.to_string())
+        .replace("{{title}}", &format!("{:?}", opts.title))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Result<NewOptions> {
+        NewOptions::parse(s.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn options() -> Result<()> {
+        let opts = args("day07 --title Camel")?;
+        assert_eq!((opts.day, opts.title.as_str(), opts.input), (7, "Camel", None));
+        assert_eq!(args("5 -i -")?.input.as_deref(), Some("-"));
+        assert!(args("0").is_err());
+        assert!(args("26").is_err());
+        assert!(args("").is_err());
+        assert!(args("1 2").is_err());
+        assert!(args("5 --fetch")?.fetch);
+        assert!(args("5 --fetch -i -").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn template() {
+        let opts = NewOptions { day: 7, title: "Say \"hi\"".into(), ..Default::default() };
+        let rendered = render(&opts);
+        assert!(rendered.contains("day/7\n"));
+        assert!(rendered.contains(r#"solution!(title: "Say \"hi\"", part1, part2);"#));
+    }
+
+    /// Copies the files of `from` into `to`, leaving out the directories of the days.
+    fn copy_crate(from: &Path, to: &Path) -> Result<()> {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let is_day = name.to_string_lossy().starts_with("day");
+            match entry.file_type()?.is_dir() {
+                true if !is_day => copy_crate(&entry.path(), &to.join(&name))?,
+                true => {}
+                false => drop(fs::copy(entry.path(), to.join(&name))?),
+            }
+        }
+        Ok(())
+    }
+
+    /// Scaffolds a day into a copy of this crate without its other days, and checks that the library and its tests,
+    /// fuzzing included, build and pass with it.
+    #[test]
+    fn template_compiles() -> Result<()> {
+        let temp = TempDir::new("template");
+        let root = &temp.0;
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        fs::create_dir_all(root)?;
+        for file in ["Cargo.toml", "Cargo.lock", "build.rs"] {
+            // The lock file is not versioned, but keeps the same versions of the dependencies when it exists.
+            if manifest_dir.join(file).exists() {
+                fs::copy(manifest_dir.join(file), root.join(file))?;
+            }
+        }
+        for dir in ["src", "templates", "benches"] {
+            copy_crate(&manifest_dir.join(dir), &root.join(dir))?;
+        }
+        new_day(
+            root,
+            &NewOptions {
+                day: 11,
+                title: "Cosmic Expansion".into(),
+                ..Default::default()
+            },
+            None,
+        )?;
+
+        // A target directory of its own, kept between runs, as the one of `cargo test` is locked.
+        let output = std::process::Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+            .args(["test", "--offline", "--quiet", "--lib", "--manifest-path"])
+            .arg(root.join("Cargo.toml"))
+            .env("CARGO_TARGET_DIR", manifest_dir.join("target/template"))
+            .output()?;
+        assert!(
+            output.status.success(),
+            "a new day does not build or pass its tests:\n{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(())
+    }
+
+    /// A temporary directory, removed when dropped, even if the test fails.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            Self(std::env::temp_dir().join(format!("aoc2023-{name}-{}", std::process::id())))
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn scaffold() -> Result<()> {
+        let temp = TempDir::new("new");
+        let root = &temp.0;
+        fs::create_dir_all(root.join("src/day03"))?;
+        let opts = |day| NewOptions { day, ..Default::default() };
+
+        let dir = new_day(root, &opts(2), None)?;
+        assert_eq!(fs::read_to_string(dir.join("input"))?, "");
+        assert!(fs::read_to_string(dir.join("mod.rs"))?.contains("day/2\n"));
+
+        fs::create_dir_all(root.join("inputs"))?;
+        fs::write(root.join("inputs/day05.txt"), "1 2 3\n")?;
+        let local = crate::fetch::Local { dir: root.join("inputs") };
+        let dir = new_day(root, &opts(5), Some(&local))?;
+        assert_eq!(fs::read_to_string(dir.join("input"))?, "1 2 3\n");
+
+        // The directory exists already.
+        assert!(new_day(root, &opts(3), None).is_err());
+        // The input cannot be imported: nothing is created.
+        let missing = NewOptions {
+            input: Some(root.join("missing").display().to_string()),
+            ..opts(4)
+        };
+        assert!(new_day(root, &missing, None).is_err());
+        assert!(!root.join("src/day04").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn features() -> Result<()> {
+        let temp = TempDir::new("features");
+        fs::create_dir_all(&temp.0)?;
+        fs::write(
+            temp.0.join("Cargo.toml"),
+            "[features]\nall = []\nday01 = []\nday02 = [\"day01\"]\n",
+        )?;
+        assert_eq!(missing_feature(&temp.0, 1)?, None);
+        assert_eq!(missing_feature(&temp.0, 2)?, None);
+        assert_eq!(missing_feature(&temp.0, 11)?.as_deref(), Some("day11 = []"));
+        Ok(())
+    }
+
+    #[test]
+    fn rollback() -> Result<()> {
+        let temp = TempDir::new("rollback");
+        let dir = temp.0.join("day04");
+        fs::create_dir_all(&temp.0)?;
+        create(&dir, &[("mod.rs", "mod"), ("input", "")])?;
+        assert_eq!(fs::read_to_string(dir.join("mod.rs"))?, "mod");
+        let err = create(&dir, &[("mod.rs", "other")]).unwrap_err();
+        assert!(err.to_string().ends_with("already exists"), "{err}");
+        assert_eq!(fs::read_to_string(dir.join("mod.rs"))?, "mod");
+        fs::remove_dir_all(&dir)?;
+
+        // The second file fails once the directory and the first file are written: both are removed.
+        let err = create(&dir, &[("mod.rs", "mod"), ("no/such/dir", "")]).unwrap_err();
+        assert!(err.to_string().contains("failed to write"), "{err}");
+        assert!(!dir.exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,393 @@
+use crate::*;
+use std::{
+    fmt::{self, Display},
+    path::Path,
+};
+
+/// A day's puzzle: how to parse its input and how to solve both parts.
+pub trait Day: Sync {
+    /// What `parse` turns the raw input into; may borrow from it.
+    type Input<'a>;
+    type Output1: IntoAnswer;
+    type Output2: IntoAnswer;
+
+    /// Day of the puzzle, from 1 to 25.
+    fn day(&self) -> u8;
+
+    /// Title of the puzzle.
+    fn title(&self) -> &'static str {
+        ""
+    }
+
+    /// Whether `parse` does any real work, i.e. whether it is worth timing and reporting on its own.
+    fn has_parse_step(&self) -> bool {
+        true
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<Self::Input<'a>>;
+    fn part1(&self, input: &Self::Input<'_>) -> Result<Self::Output1>;
+    fn part2(&self, input: &Self::Input<'_>) -> Result<Self::Output2>;
+}
+
+/// Step of a solution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Part(u8),
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Parse => f.pad("parse"),
+            Self::Part(part) => f.pad(&format!("part {part}")),
+        }
+    }
+}
+
+/// Drives the phases of a solution, e.g. to time them or to collect the answers.
+pub trait Harness {
+    /// Runs `phase` by calling `f` one or more times. `f` returns the answer of a part, or `None` when parsing.
+    /// Returns whether the phase succeeded; the parts are skipped if parsing fails.
+    fn phase(&mut self, phase: Phase, f: &mut dyn FnMut() -> Result<Option<Answer>>) -> bool;
+}
+
+/// Object-safe view of a [`Day`], as stored in the registry.
+pub trait DynDay: Sync {
+    fn day(&self) -> u8;
+    fn title(&self) -> &'static str;
+    /// Parses `input` and solves the given `parts` through `harness`.
+    fn run(&self, input: &str, parts: &[u8], harness: &mut dyn Harness);
+}
+
+impl<D: Day> DynDay for D {
+    fn day(&self) -> u8 {
+        Day::day(self)
+    }
+
+    fn title(&self) -> &'static str {
+        Day::title(self)
+    }
+
+    fn run(&self, input: &str, parts: &[u8], harness: &mut dyn Harness) {
+        let mut parsed = None;
+        let parse = &mut || {
+            parsed = None;
+            parsed = Some(self.parse(input)?);
+            Ok(None)
+        };
+        let ok = match self.has_parse_step() {
+            true => harness.phase(Phase::Parse, parse),
+            // Without a parse step of its own, parsing is only reported when it fails.
+            false => parse().is_ok() || harness.phase(Phase::Parse, parse),
+        };
+        let Some(parsed) = parsed.filter(|_| ok) else { return };
+
+        for &part in parts {
+            harness.phase(Phase::Part(part), &mut || match part {
+                1 => self.part1(&parsed).map(|x| Some(x.into_answer())),
+                2 => self.part2(&parsed).map(|x| Some(x.into_answer())),
+                _ => bail!("no such part: {part}"),
+            });
+        }
+    }
+}
+
+/// A [`Day`] made of a parse function and two part functions taking its output. Built by `solution!`.
+pub struct FnDay<T, O1, O2> {
+    pub day:   u8,
+    pub title: &'static str,
+    pub parse: fn(&str) -> Result<T>,
+    pub part1: fn(&T) -> Result<O1>,
+    pub part2: fn(&T) -> Result<O2>,
+}
+
+impl<T, O1: IntoAnswer, O2: IntoAnswer> Day for FnDay<T, O1, O2> {
+    type Input<'a> = T;
+    type Output1 = O1;
+    type Output2 = O2;
+
+    fn day(&self) -> u8 {
+        self.day
+    }
+
+    fn title(&self) -> &'static str {
+        self.title
+    }
+
+    fn parse(&self, input: &str) -> Result<T> {
+        (self.parse)(input)
+    }
+
+    fn part1(&self, input: &T) -> Result<O1> {
+        (self.part1)(input)
+    }
+
+    fn part2(&self, input: &T) -> Result<O2> {
+        (self.part2)(input)
+    }
+}
+
+/// A [`Day`] whose parts work on the raw input directly. Built by `solution!`.
+pub struct StrDay<O1, O2> {
+    pub day:   u8,
+    pub title: &'static str,
+    pub part1: fn(&str) -> Result<O1>,
+    pub part2: fn(&str) -> Result<O2>,
+}
+
+impl<O1: IntoAnswer, O2: IntoAnswer> Day for StrDay<O1, O2> {
+    type Input<'a> = &'a str;
+    type Output1 = O1;
+    type Output2 = O2;
+
+    fn day(&self) -> u8 {
+        self.day
+    }
+
+    fn title(&self) -> &'static str {
+        self.title
+    }
+
+    fn has_parse_step(&self) -> bool {
+        false
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> Result<&'a str> {
+        Ok(input)
+    }
+
+    fn part1(&self, input: &&str) -> Result<O1> {
+        (self.part1)(input)
+    }
+
+    fn part2(&self, input: &&str) -> Result<O2> {
+        (self.part2)(input)
+    }
+}
+
+/// Day number of a `dayNN` module, given its `module_path!()`.
+#[doc(hidden)]
+pub const fn day_of_module(path: &str) -> u8 {
+    match path.as_bytes() {
+        &[.., tens @ b'0'..=b'9', ones @ b'0'..=b'9'] => (tens - b'0') * 10 + (ones - b'0'),
+        _ => 0,
+    }
+}
+
+/// Typed expected answer of part 1 of `day`. Used by `solution!`.
+#[doc(hidden)]
+pub fn expected_part1<D: Day, E: Expected<D::Output1>>(_day: &D, expected: E) -> Answer {
+    expected.into_expected()
+}
+
+/// Typed expected answer of part 2 of `day`. Used by `solution!`.
+#[doc(hidden)]
+pub fn expected_part2<D: Day, E: Expected<D::Output2>>(_day: &D, expected: E) -> Answer {
+    expected.into_expected()
+}
+
+/// A puzzle input embedded in the binary, with the expected answers for it.
+#[derive(Clone, Copy)]
+pub struct Input {
+    /// `input` for the `input` file of the day, or the file name of `inputs/<name>.txt`.
+    pub name:     &'static str,
+    pub text:     &'static str,
+    /// Expected answers of both parts for `text`, as written in `solution!`, or in the `inputs/<name>.part1` and
+    /// `inputs/<name>.part2` files.
+    pub expected: [Option<fn() -> Answer>; 2],
+}
+
+impl Input {
+    /// Expected answer of `part` (1 or 2), if it is known.
+    pub fn expected(&self, part: u8) -> Option<Answer> {
+        self.expected
+            .get(usize::from(part).checked_sub(1)?)
+            .copied()
+            .flatten()
+            .map(|f| f())
+    }
+}
+
+/// `input`, followed by the `N - 1` named inputs of `day`. Used by `solution!`.
+#[doc(hidden)]
+pub const fn with_named_inputs<const N: usize>(input: Input, day: u8) -> [Input; N] {
+    let named = crate::named_inputs(day);
+    let mut inputs = [input; N];
+    let mut i = 1;
+    while i < N {
+        inputs[i] = named[i - 1];
+        i += 1;
+    }
+    inputs
+}
+
+/// A registered day: its puzzle and embedded inputs.
+#[derive(Clone, Copy)]
+pub struct Solution {
+    pub day:      &'static dyn DynDay,
+    /// Path of the file registering the day, e.g. `src/day05/mod.rs`: relative to the crate root, or absolute.
+    pub source:   &'static str,
+    /// The `input` file of the day first, then any named inputs.
+    pub inputs:   &'static [Input],
+    /// Generates a valid input made of about `size` items (lines, cards, nodes, tiles...), so that its length grows
+    /// linearly with `size`. Days whose input does not grow have none.
+    pub generate: Option<fn(usize, &mut Rng) -> String>,
+}
+
+impl Solution {
+    /// The `input` file of the day.
+    pub fn input(&self) -> &'static str {
+        self.inputs.first().map_or("", |input| input.text)
+    }
+
+    /// Examples in the `examples` directory next to the day's sources, see [`load_examples`].
+    pub fn examples(&self) -> Result<Vec<Example>> {
+        load_examples(
+            &Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join(self.source)
+                .with_file_name("examples"),
+        )
+    }
+
+    /// Parses `input` and solves the given `parts` through `harness`.
+    pub fn run(&self, input: &str, parts: &[u8], harness: &mut dyn Harness) {
+        self.day.run(input, parts, harness)
+    }
+
+    /// Solves `part` of `input` once.
+    pub fn solve(&self, part: u8, input: &str) -> Result<Answer> {
+        struct Once(Option<Result<Answer>>);
+        impl Harness for Once {
+            fn phase(&mut self, _: Phase, f: &mut dyn FnMut() -> Result<Option<Answer>>) -> bool {
+                match f() {
+                    Ok(None) => true,
+                    Ok(Some(answer)) => {
+                        self.0 = Some(Ok(answer));
+                        true
+                    }
+                    Err(e) => {
+                        self.0 = Some(Err(e));
+                        false
+                    }
+                }
+            }
+        }
+
+        let mut once = Once(None);
+        self.run(input, &[part], &mut once);
+        once.0.unwrap_or_else(|| Err(anyhow!("no answer for part {part}")))
+    }
+
+    pub fn part1(&self, input: &str) -> Result<Answer> {
+        self.solve(1, input)
+    }
+
+    pub fn part2(&self, input: &str) -> Result<Answer> {
+        self.solve(2, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A hand-written day whose parsed input borrows from the raw input.
+    struct Words;
+
+    impl Day for Words {
+        type Input<'a> = Vec<&'a str>;
+        type Output1 = usize;
+        type Output2 = String;
+
+        fn day(&self) -> u8 {
+            42
+        }
+
+        fn parse<'a>(&self, input: &'a str) -> Result<Vec<&'a str>> {
+            let words = input.split_whitespace().collect_vec();
+            ensure!(!words.is_empty(), "no words");
+            Ok(words)
+        }
+
+        fn part1(&self, words: &Vec<&str>) -> Result<usize> {
+            Ok(words.len())
+        }
+
+        fn part2(&self, words: &Vec<&str>) -> Result<String> {
+            words
+                .iter()
+                .max_by_key(|w| w.len())
+                .map(|w| w.to_string())
+                .ok_or_else(|| anyhow!("no words"))
+        }
+    }
+
+    const SOLUTION: Solution = Solution {
+        day:      &Words,
+        source:   "src/day42/mod.rs",
+        generate: None,
+        inputs:   &[
+            Input {
+                name:     "input",
+                text:     "a bc def",
+                expected: [Some(|| expected_part1(&Words, 3)), None],
+            },
+            Input {
+                name:     "other",
+                text:     "x yz",
+                expected: [None, Some(|| expected_part2(&Words, "yz"))],
+            },
+        ],
+    };
+
+    #[test]
+    fn solve() -> Result<()> {
+        assert_eq!(SOLUTION.day.day(), 42);
+        assert_eq!(SOLUTION.part1(SOLUTION.input())?, 3.into_answer());
+        assert_eq!(SOLUTION.part2(SOLUTION.input())?, "def".into_answer());
+        let [input, other] = SOLUTION.inputs else {
+            panic!("expected two inputs")
+        };
+        assert_eq!((input.expected(1), input.expected(2)), (Some(3.into_answer()), None));
+        assert_eq!((other.expected(1), other.expected(2)), (None, Some("yz".into_answer())));
+        assert_eq!(SOLUTION.part2(other.text)?, "yz".into_answer());
+        assert!(SOLUTION.examples()?.is_empty());
+        assert_eq!(
+            SOLUTION.part1(" ").err().map(|e| e.to_string()).as_deref(),
+            Some("no words")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn phases() {
+        struct Log(Vec<(Phase, bool)>);
+        impl Harness for Log {
+            fn phase(&mut self, phase: Phase, f: &mut dyn FnMut() -> Result<Option<Answer>>) -> bool {
+                let ok = f().is_ok();
+                self.0.push((phase, ok));
+                ok
+            }
+        }
+
+        let mut log = Log(vec![]);
+        SOLUTION.run("x", &[2, 1], &mut log);
+        assert_eq!(log.0, [
+            (Phase::Parse, true),
+            (Phase::Part(2), true),
+            (Phase::Part(1), true)
+        ]);
+
+        let mut log = Log(vec![]);
+        SOLUTION.run("", &[1, 2], &mut log);
+        assert_eq!(log.0, [(Phase::Parse, false)]);
+    }
+
+    #[test]
+    fn module_day() {
+        assert_eq!(day_of_module("aoc2023::day05"), 5);
+        assert_eq!(day_of_module("aoc2023::day25"), 25);
+        assert_eq!(day_of_module("aoc2023::tests"), 0);
+    }
+}
@@ -10,8 +10,8 @@ macro_rules! test {
             $(
                 #[test]
                 fn $name() -> Result<(), Box<dyn std::error::Error>> {
-                    let res = (SOLUTION.$part)($input)?;
-                    assert_eq!(res, stringify!($expected));
+                    let res = SOLUTION.$part($input)?;
+                    assert_eq!(res, $crate::IntoAnswer::into_answer($expected));
                     Ok(())
                 }
             )*
@@ -19,30 +19,69 @@ macro_rules! test {
     };
 }
 
+/// Checks `$part` against every embedded input whose answer to it is known, both parts against the examples in
+/// the `examples` directory of the day, or that both parts accept inputs from the day's generator.
 #[macro_export]
 macro_rules! solution_test {
-    ($part:ident => $answer:expr) => {
+    (examples) => {
+        #[test]
+        fn examples() -> Result<(), Box<dyn std::error::Error>> {
+            for example in super::SOLUTION.examples()? {
+                for (part, expected) in (1..).zip(example.expected) {
+                    if let Some(expected) = expected {
+                        let res = super::SOLUTION.solve(part, &example.text)?;
+                        assert_eq!(
+                            expected, res,
+                            "wrong answer to part {part} of example {}",
+                            example.name
+                        );
+                    }
+                }
+            }
+            Ok(())
+        }
+    };
+    (generated) => {
+        #[test]
+        fn generated() -> Result<(), Box<dyn std::error::Error>> {
+            let Some(generate) = super::SOLUTION.generate else {
+                return Ok(());
+            };
+            for (seed, size) in [1, 2, 10, 100, 1000].into_iter().enumerate() {
+                let input = generate(size, &mut $crate::Rng::new(seed as u64));
+                for part in [1, 2] {
+                    if let Err(e) = super::SOLUTION.solve(part, &input) {
+                        panic!("part {part} fails on a generated input of size {size}: {e}\n{input}");
+                    }
+                }
+            }
+            Ok(())
+        }
+    };
+    ($part:ident, $index:literal) => {
         #[test]
         fn $part() -> Result<(), Box<dyn std::error::Error>> {
-            let input = include_str!("input");
-            let res = (super::SOLUTION.$part)(input)?;
-            assert_eq!(
-                stringify!($answer).trim_matches(|c| c == '"').trim_matches('\n'),
-                res
-            );
+            for input in super::SOLUTION.inputs {
+                if let Some(expected) = input.expected($index) {
+                    let res = super::SOLUTION.$part(input.text)?;
+                    assert_eq!(expected, res, "wrong answer for {}", input.name);
+                }
+            }
             Ok(())
         }
     };
 }
 
+/// Benchmarks `$part` with `#[bench]`, on nightly only; `benches/solutions.rs` benchmarks every part on stable.
 #[macro_export]
 macro_rules! solution_bench {
     ($part:ident) => {
+        #[cfg(feature = "nightly")]
         #[bench]
         fn $part(b: &mut test::Bencher) {
-            let input = include_str!("input");
+            let input = super::SOLUTION.input();
             b.iter(|| {
-                (super::SOLUTION.$part)(input).unwrap();
+                super::SOLUTION.$part(input).unwrap();
             })
         }
     };
@@ -1,20 +1,23 @@
+// https://adventofcode.com/2023/day/{{day}}
 use crate::*;
 
 fn part1(_input: &str) -> Result<usize> {
-    todo!()
+    bail!("not solved yet")
 }
 
 fn part2(_input: &str) -> Result<usize> {
-    todo!()
+    bail!("not solved yet")
 }
 
-solution!(part1 => todo!(), part2 => todo!());
+solution!(title: {{title}}, part1, part2);
 
 #[cfg(test)]
 mod tests {
+    // Uncomment the tests once the answers to the example are known.
+    #[allow(dead_code)]
     const EXAMPLE: &str = indoc::indoc! {"
     "};
 
-    crate::test!(part1, t1: EXAMPLE => todo!());
-    crate::test!(part2, t1: EXAMPLE => todo!());
+    // crate::test!(part1, t1: EXAMPLE => 0);
+    // crate::test!(part2, t1: EXAMPLE => 0);
 }